@@ -1,6 +1,7 @@
 use anyhow::Result;
 use serde_json::Value;
 use vtcode_core::config::constants::tools;
+use vtcode_core::tools::file_classifier::classify_file_type;
 use vtcode_core::utils::ansi::{AnsiRenderer, MessageStyle};
 
 use super::styles::{GitStyles, LsStyles, select_line_style};
@@ -35,6 +36,35 @@ fn get_u64(val: &Value, key: &str) -> Option<u64> {
     val.get(key).and_then(|v| v.as_u64())
 }
 
+/// Number of leading bytes sampled when sniffing content for binary data
+const BINARY_SNIFF_SAMPLE_SIZE: usize = 8000;
+
+/// Ratio of non-printable bytes (outside printable ASCII, tab, carriage
+/// return, and newline) within the sample above which content is treated as
+/// binary. Bytes of multi-byte UTF-8 characters count as non-printable.
+const BINARY_NON_PRINTABLE_RATIO: f64 = 0.3;
+
+/// Detect whether `bytes` looks like binary content, `file`/`coreutils`-style:
+/// a NUL byte anywhere in the sample is a binary tell by itself, otherwise a
+/// high ratio of non-printable bytes within the first few KB is
+pub(super) fn is_binary_content(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(BINARY_SNIFF_SAMPLE_SIZE)];
+    if sample.is_empty() {
+        return false;
+    }
+
+    if sample.contains(&0) {
+        return true;
+    }
+
+    let non_printable = sample
+        .iter()
+        .filter(|&&b| !(b == b'\t' || b == b'\n' || b == b'\r' || (0x20..0x7f).contains(&b)))
+        .count();
+
+    (non_printable as f64 / sample.len() as f64) > BINARY_NON_PRINTABLE_RATIO
+}
+
 pub(crate) fn render_write_file_preview(
     renderer: &mut AnsiRenderer,
     payload: &Value,
@@ -45,6 +75,11 @@ pub(crate) fn render_write_file_preview(
         renderer.line(MessageStyle::Info, &format!("  encoding: {}", encoding))?;
     }
 
+    if let Some(path) = get_string(payload, "path") {
+        let category = classify_file_type(path, ls_styles.type_overrides());
+        renderer.line(MessageStyle::Info, &format!("  type: {}", category.label()))?;
+    }
+
     if get_bool(payload, "created") {
         renderer.line(MessageStyle::Response, "  File created")?;
     }
@@ -80,7 +115,12 @@ pub(crate) fn render_write_file_preview(
         renderer.line(MessageStyle::Info, "  No diff changes to display.")?;
     }
 
-    if !diff_content.is_empty() {
+    if !diff_content.is_empty() && is_binary_content(diff_content.as_bytes()) {
+        renderer.line(
+            MessageStyle::Info,
+            &format!("  Binary file, {} bytes changed", diff_content.len()),
+        )?;
+    } else if !diff_content.is_empty() {
         renderer.line(MessageStyle::Info, "[diff]")?;
         // Use higher limit for diffs since they're already filtered by token limit in render_stream_section
         // Diffs are usually sparse (many unchanged lines) so line-based preview is reasonable here
@@ -104,7 +144,8 @@ pub(crate) fn render_write_file_preview(
 pub(crate) fn render_list_dir_output(
     renderer: &mut AnsiRenderer,
     val: &Value,
-    _ls_styles: &LsStyles,
+    git_styles: &GitStyles,
+    ls_styles: &LsStyles,
 ) -> Result<()> {
     if let Some(path) = get_string(val, "path") {
         renderer.line(MessageStyle::Info, &format!("  {}", path))?;
@@ -141,11 +182,14 @@ pub(crate) fn render_list_dir_output(
     if let Some(items) = val.get("items").and_then(|v| v.as_array()) {
         if items.is_empty() {
             renderer.line(MessageStyle::Info, "  (empty directory)")?;
+        } else if get_string(val, "view") == Some("tree") {
+            render_tree_items(renderer, items, "", git_styles, ls_styles)?;
         } else {
             for item in items {
                 if let Some(name) = get_string(item, "name") {
                     let item_type = get_string(item, "type").unwrap_or("file");
                     let size = get_u64(item, "size");
+                    let git_status = get_string(item, "git_status");
 
                     let display_name = if item_type == "directory" {
                         format!("{}/", name)
@@ -153,13 +197,24 @@ pub(crate) fn render_list_dir_output(
                         name.to_string()
                     };
 
+                    let badge = git_status_badge(git_status);
+
                     let display = if let Some(size_bytes) = size {
-                        format!("  {} ({})", display_name, format_size(size_bytes))
+                        format!("  {badge}{} ({})", display_name, format_size(size_bytes))
                     } else {
-                        format!("  {}", display_name)
+                        format!("  {badge}{}", display_name)
                     };
 
-                    renderer.line(MessageStyle::Response, &display)?;
+                    if let Some(style) = git_status.and_then(|status| git_styles.style_for_status(status)) {
+                        renderer.line_with_style(style, &display)?;
+                    } else if let Some(style) = (item_type != "directory")
+                        .then(|| classify_file_type(name, ls_styles.type_overrides()))
+                        .and_then(|category| ls_styles.style_for_category(category))
+                    {
+                        renderer.line_with_style(style, &display)?;
+                    } else {
+                        renderer.line(MessageStyle::Response, &display)?;
+                    }
                 }
             }
         }
@@ -181,15 +236,100 @@ pub(crate) fn render_list_dir_output(
     Ok(())
 }
 
-pub(crate) fn render_read_file_output(renderer: &mut AnsiRenderer, val: &Value) -> Result<()> {
+/// Render directory entries as a `exa --tree`-style nested listing, using
+/// Unicode branch guides (`├── `/`└── `) and `│   ` continuation prefixes for
+/// ancestors that still have later siblings. Recurses into each directory's
+/// `"children"` array, if present.
+fn render_tree_items(
+    renderer: &mut AnsiRenderer,
+    items: &[Value],
+    prefix: &str,
+    git_styles: &GitStyles,
+    ls_styles: &LsStyles,
+) -> Result<()> {
+    let last_index = items.len().saturating_sub(1);
+
+    for (index, item) in items.iter().enumerate() {
+        let Some(name) = get_string(item, "name") else {
+            continue;
+        };
+
+        let is_last = index == last_index;
+        let guide = if is_last { "└── " } else { "├── " };
+        let item_type = get_string(item, "type").unwrap_or("file");
+        let size = get_u64(item, "size");
+        let git_status = get_string(item, "git_status");
+        let badge = git_status_badge(git_status);
+
+        let display_name = if item_type == "directory" {
+            format!("{}/", name)
+        } else {
+            name.to_string()
+        };
+
+        let display = if let Some(size_bytes) = size {
+            format!(
+                "  {prefix}{guide}{badge}{display_name} ({})",
+                format_size(size_bytes)
+            )
+        } else {
+            format!("  {prefix}{guide}{badge}{display_name}")
+        };
+
+        if let Some(style) = git_status.and_then(|status| git_styles.style_for_status(status)) {
+            renderer.line_with_style(style, &display)?;
+        } else if let Some(style) = (item_type != "directory")
+            .then(|| classify_file_type(name, ls_styles.type_overrides()))
+            .and_then(|category| ls_styles.style_for_category(category))
+        {
+            renderer.line_with_style(style, &display)?;
+        } else {
+            renderer.line(MessageStyle::Response, &display)?;
+        }
+
+        if let Some(children) = item.get("children").and_then(|v| v.as_array())
+            && !children.is_empty()
+        {
+            let child_prefix = format!("{prefix}{}", if is_last { "    " } else { "│   " });
+            render_tree_items(renderer, children, &child_prefix, git_styles, ls_styles)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Render an `exa --git`-style two-character status badge (e.g. `"M "`,
+/// `"??"`) followed by a trailing space, or an empty string for entries with
+/// no `git_status`.
+fn git_status_badge(git_status: Option<&str>) -> String {
+    match git_status {
+        Some(status) => format!("{:<2} ", status),
+        None => String::new(),
+    }
+}
+
+pub(crate) fn render_read_file_output(
+    renderer: &mut AnsiRenderer,
+    val: &Value,
+    ls_styles: &LsStyles,
+) -> Result<()> {
     if let Some(encoding) = get_string(val, "encoding") {
         renderer.line(MessageStyle::Info, &format!("  encoding: {}", encoding))?;
     }
 
+    if let Some(path) = get_string(val, "path") {
+        let category = classify_file_type(path, ls_styles.type_overrides());
+        renderer.line(MessageStyle::Info, &format!("  type: {}", category.label()))?;
+    }
+
     if let Some(size) = get_u64(val, "size") {
+        let is_binary = get_string(val, "content")
+            .map(|content| is_binary_content(content.as_bytes()))
+            .unwrap_or(false);
+        let suffix = if is_binary { " (binary)" } else { "" };
         renderer.line(
             MessageStyle::Info,
-            &format!("  size: {}", format_size(size)),
+            &format!("  size: {}{suffix}", format_size(size)),
         )?;
     }
 
@@ -202,17 +342,190 @@ pub(crate) fn render_read_file_output(renderer: &mut AnsiRenderer, val: &Value)
     Ok(())
 }
 
-/// Render diff content lines with proper truncation and styling
+/// ANSI toggles bracketing a word-level diff emphasis run (bold + inverse),
+/// layered on top of whatever base color `select_line_style` picked for the
+/// surrounding line.
+const EMPHASIS_ON: &str = "\x1b[1;7m";
+const EMPHASIS_OFF: &str = "\x1b[22;27m";
+
+/// Which side of a word-level diff a token belongs to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TokenDiff {
+    Common,
+    Changed,
+}
+
+/// Split a line into alternating runs of whitespace and non-whitespace, so
+/// word-level diffing doesn't merge adjacent words or collapse spacing.
+fn tokenize_diff_line(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut current_is_whitespace: Option<bool> = None;
+
+    for (i, c) in line.char_indices() {
+        let is_whitespace = c.is_whitespace();
+        match current_is_whitespace {
+            None => current_is_whitespace = Some(is_whitespace),
+            Some(prev) if prev != is_whitespace => {
+                tokens.push(&line[start..i]);
+                start = i;
+                current_is_whitespace = Some(is_whitespace);
+            }
+            _ => {}
+        }
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+
+    tokens
+}
+
+/// Classify each token of `old`/`new` as common or changed, using the
+/// longest common subsequence of tokens so unchanged words in the middle of
+/// an edited line stay un-emphasized.
+fn diff_tokens<'a>(
+    old: &[&'a str],
+    new: &[&'a str],
+) -> (Vec<(TokenDiff, &'a str)>, Vec<(TokenDiff, &'a str)>) {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_out = Vec::with_capacity(n);
+    let mut new_out = Vec::with_capacity(m);
+    let (mut i, mut j) = (0, 0);
+
+    while i < n && j < m {
+        if old[i] == new[j] {
+            old_out.push((TokenDiff::Common, old[i]));
+            new_out.push((TokenDiff::Common, new[j]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            old_out.push((TokenDiff::Changed, old[i]));
+            i += 1;
+        } else {
+            new_out.push((TokenDiff::Changed, new[j]));
+            j += 1;
+        }
+    }
+    while i < n {
+        old_out.push((TokenDiff::Changed, old[i]));
+        i += 1;
+    }
+    while j < m {
+        new_out.push((TokenDiff::Changed, new[j]));
+        j += 1;
+    }
+
+    (old_out, new_out)
+}
+
+/// Render one side of a word-diffed pair: the line's base style comes from
+/// `select_line_style` as usual, with changed token runs additionally
+/// wrapped in an inverse/bold emphasis so only the differing words stand out.
+fn render_classified_diff_line(
+    renderer: &mut AnsiRenderer,
+    original_line: &str,
+    tokens: &[(TokenDiff, &str)],
+    git_styles: &GitStyles,
+    ls_styles: &LsStyles,
+) -> Result<()> {
+    let marker = original_line.chars().next().unwrap_or(' ');
+    let mut display = String::from("  ");
+    display.push(marker);
+
+    for (kind, token) in tokens {
+        if *kind == TokenDiff::Common {
+            display.push_str(token);
+        } else {
+            display.push_str(EMPHASIS_ON);
+            display.push_str(token);
+            display.push_str(EMPHASIS_OFF);
+        }
+    }
+
+    if let Some(style) =
+        select_line_style(Some(tools::WRITE_FILE), original_line, git_styles, ls_styles)
+    {
+        renderer.line_with_style(style, &display)?;
+    } else {
+        renderer.line(MessageStyle::Response, &display)?;
+    }
+
+    Ok(())
+}
+
+/// Render a removed/added line pair with word-level highlighting: tokenize
+/// each line's content (marker stripped), align tokens via LCS, and
+/// emphasize only the runs that differ.
+fn render_word_diff_pair(
+    renderer: &mut AnsiRenderer,
+    removed_line: &str,
+    added_line: &str,
+    git_styles: &GitStyles,
+    ls_styles: &LsStyles,
+) -> Result<()> {
+    let removed_truncated = truncate_text_safe(removed_line, MAX_DIFF_LINE_LENGTH);
+    let added_truncated = truncate_text_safe(added_line, MAX_DIFF_LINE_LENGTH);
+
+    let removed_tokens = tokenize_diff_line(removed_truncated.get(1..).unwrap_or(""));
+    let added_tokens = tokenize_diff_line(added_truncated.get(1..).unwrap_or(""));
+
+    let (removed_classified, added_classified) = diff_tokens(&removed_tokens, &added_tokens);
+
+    render_classified_diff_line(
+        renderer,
+        removed_truncated,
+        &removed_classified,
+        git_styles,
+        ls_styles,
+    )?;
+    render_classified_diff_line(
+        renderer,
+        added_truncated,
+        &added_classified,
+        git_styles,
+        ls_styles,
+    )?;
+
+    Ok(())
+}
+
+/// Whether `line` is a removed/added content line (`-`/`+` prefix), as
+/// opposed to a unified-diff header line (`---`/`+++`).
+fn is_diff_content_line(line: &str, marker: char) -> bool {
+    let header = if marker == '-' { "---" } else { "+++" };
+    line.starts_with(marker) && !line.starts_with(header)
+}
+
+/// Render diff content lines with proper truncation and styling. A removed
+/// block immediately followed by an added block of equal length gets
+/// word-level highlighting via `render_word_diff_pair`; everything else
+/// falls back to whole-line styling. The `MAX_DIFF_LINES` cap counts source
+/// diff lines consumed, not rendered output lines.
 fn render_diff_content(
     renderer: &mut AnsiRenderer,
     diff_content: &str,
     git_styles: &GitStyles,
     ls_styles: &LsStyles,
 ) -> Result<()> {
+    let lines: Vec<&str> = diff_content.lines().collect();
+    let total_lines = lines.len();
     let mut line_count = 0;
-    let total_lines = diff_content.lines().count();
+    let mut index = 0;
 
-    for line in diff_content.lines() {
+    while index < lines.len() {
         if line_count >= MAX_DIFF_LINES {
             renderer.line(
                 MessageStyle::Info,
@@ -224,6 +537,39 @@ fn render_diff_content(
             break;
         }
 
+        if is_diff_content_line(lines[index], '-') {
+            let removed_start = index;
+            let mut removed_end = removed_start;
+            while removed_end < lines.len() && is_diff_content_line(lines[removed_end], '-') {
+                removed_end += 1;
+            }
+
+            let added_start = removed_end;
+            let mut added_end = added_start;
+            while added_end < lines.len() && is_diff_content_line(lines[added_end], '+') {
+                added_end += 1;
+            }
+
+            let removed_count = removed_end - removed_start;
+            let added_count = added_end - added_start;
+
+            if removed_count == added_count && removed_count > 0 {
+                for offset in 0..removed_count {
+                    render_word_diff_pair(
+                        renderer,
+                        lines[removed_start + offset],
+                        lines[added_start + offset],
+                        git_styles,
+                        ls_styles,
+                    )?;
+                }
+                line_count += removed_count + added_count;
+                index = added_end;
+                continue;
+            }
+        }
+
+        let line = lines[index];
         let truncated = truncate_text_safe(line, MAX_DIFF_LINE_LENGTH);
         let display = format!("  {truncated}");
 
@@ -235,6 +581,7 @@ fn render_diff_content(
             renderer.line(MessageStyle::Response, &display)?;
         }
         line_count += 1;
+        index += 1;
     }
 
     Ok(())
@@ -255,3 +602,64 @@ fn format_size(bytes: u64) -> String {
         format!("{} B", bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_nul_bytes_as_binary() {
+        assert!(is_binary_content(b"hello\x00world"));
+    }
+
+    #[test]
+    fn detects_high_non_printable_ratio_as_binary() {
+        let bytes: Vec<u8> = (1u8..=255).collect();
+        assert!(is_binary_content(&bytes));
+    }
+
+    #[test]
+    fn valid_utf8_diff_is_not_binary() {
+        let diff = "-old line\n+new line\n context line\n";
+        assert!(!is_binary_content(diff.as_bytes()));
+    }
+
+    #[test]
+    fn empty_content_is_not_binary() {
+        assert!(!is_binary_content(b""));
+    }
+
+    #[test]
+    fn git_status_badge_pads_modified_to_two_columns() {
+        assert_eq!(git_status_badge(Some("M")), "M  ");
+    }
+
+    #[test]
+    fn git_status_badge_renders_added_as_is() {
+        assert_eq!(git_status_badge(Some("A")), "A  ");
+    }
+
+    #[test]
+    fn git_status_badge_renders_deleted_as_is() {
+        assert_eq!(git_status_badge(Some("D")), "D  ");
+    }
+
+    #[test]
+    fn git_status_badge_renders_renamed_as_is() {
+        assert_eq!(git_status_badge(Some("R")), "R  ");
+    }
+
+    #[test]
+    fn git_status_badge_renders_untracked_two_char_status_unpadded() {
+        assert_eq!(git_status_badge(Some("??")), "?? ");
+    }
+
+    #[test]
+    fn git_status_badge_is_empty_for_entries_without_the_field() {
+        assert_eq!(git_status_badge(None), "");
+    }
+
+    // `GitStyles::style_for_status` (the other function this request covers)
+    // lives in a `super::styles` module that isn't present in this checkout,
+    // so it has no type to construct and can't be unit tested from here.
+}
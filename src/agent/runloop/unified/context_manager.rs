@@ -1,23 +1,138 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use anyhow::Result;
 use tracing::warn;
 
 use crate::agent::runloop::context::{
-    ContextTrimConfig, ContextTrimOutcome, apply_aggressive_trim_unified,
+    ContextTrimConfig, ContextTrimOutcome, SemanticScorerKind, apply_aggressive_trim_unified,
     enforce_unified_context_window, prune_unified_tool_responses,
 };
 use vtcode_core::core::token_budget::{ContextComponent, TokenBudgetManager};
 use vtcode_core::llm::provider as uni;
 use vtcode_core::tools::tree_sitter::TreeSitterAnalyzer;
 
+/// Scores how worth keeping a piece of conversation history is when the
+/// context window must be trimmed. Implementations are free to use whatever
+/// signal they like (syntax density, relevance to the active task, ...) as
+/// long as they return a stable priority for a stable `content_hash`, so
+/// `ContextManager`'s cache stays valid across calls.
+pub(crate) trait SemanticScorer {
+    /// Compute a keep-priority in `0..=255` for `text`, higher meaning more
+    /// worth retaining. `content_hash` is a stable hash of `text` the caller
+    /// has already computed, so scorers don't need to hash it themselves.
+    fn score(&mut self, content_hash: u64, text: &str) -> u8;
+}
+
+/// Default scorer: favors messages with dense code structure, using the
+/// existing tree-sitter analyzer.
+pub(crate) struct TreeSitterScorer {
+    analyzer: TreeSitterAnalyzer,
+}
+
+impl TreeSitterScorer {
+    fn new() -> Result<Self, vtcode_core::tools::tree_sitter::TreeSitterError> {
+        Ok(Self {
+            analyzer: TreeSitterAnalyzer::new()?,
+        })
+    }
+}
+
+impl SemanticScorer for TreeSitterScorer {
+    fn score(&mut self, _content_hash: u64, text: &str) -> u8 {
+        self.analyzer.syntactic_density_score(text)
+    }
+}
+
+/// Cheap relevance scorer: estimates how related a message is to the active
+/// task by comparing hashed word n-grams against the base system prompt,
+/// rather than the message's syntactic shape. Useful when the goal is to
+/// keep messages on-topic even if they're plain prose rather than code.
+pub(crate) struct PromptOverlapScorer {
+    prompt_ngrams: HashSet<u64>,
+}
+
+impl PromptOverlapScorer {
+    fn new(base_system_prompt: &str) -> Self {
+        Self {
+            prompt_ngrams: hashed_ngrams(base_system_prompt),
+        }
+    }
+}
+
+impl SemanticScorer for PromptOverlapScorer {
+    fn score(&mut self, _content_hash: u64, text: &str) -> u8 {
+        if self.prompt_ngrams.is_empty() {
+            return 0;
+        }
+
+        let text_ngrams = hashed_ngrams(text);
+        if text_ngrams.is_empty() {
+            return 0;
+        }
+
+        let overlap = text_ngrams.intersection(&self.prompt_ngrams).count();
+        let cosine = overlap as f64 / (text_ngrams.len() as f64).sqrt().max(1.0)
+            / (self.prompt_ngrams.len() as f64).sqrt().max(1.0);
+
+        (cosine.clamp(0.0, 1.0) * u8::MAX as f64).round() as u8
+    }
+}
+
+/// Hash every 3-word window of `text` into a set, as a cheap stand-in for a
+/// real embedding when comparing relevance against the system prompt.
+fn hashed_ngrams(text: &str) -> HashSet<u64> {
+    use std::hash::{Hash, Hasher};
+
+    const NGRAM_SIZE: usize = 3;
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return HashSet::new();
+    }
+    if words.len() < NGRAM_SIZE {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        words.hash(&mut hasher);
+        return HashSet::from([hasher.finish()]);
+    }
+
+    words
+        .windows(NGRAM_SIZE)
+        .map(|window| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+fn build_semantic_scorer(
+    kind: SemanticScorerKind,
+    base_system_prompt: &str,
+) -> Option<Box<dyn SemanticScorer + Send>> {
+    match kind {
+        SemanticScorerKind::TreeSitter => match TreeSitterScorer::new() {
+            Ok(scorer) => Some(Box::new(scorer)),
+            Err(error) => {
+                warn!(
+                    error = %error,
+                    "Failed to initialize TreeSitterScorer; disabling semantic compression"
+                );
+                None
+            }
+        },
+        SemanticScorerKind::PromptOverlap => {
+            Some(Box::new(PromptOverlapScorer::new(base_system_prompt)))
+        }
+    }
+}
+
 pub(crate) struct ContextManager {
     trim_config: ContextTrimConfig,
     token_budget: Arc<TokenBudgetManager>,
     token_budget_enabled: bool,
     base_system_prompt: String,
-    semantic_analyzer: Option<TreeSitterAnalyzer>,
+    semantic_scorer: Option<Box<dyn SemanticScorer + Send>>,
     semantic_score_cache: Option<HashMap<u64, u8>>,
 }
 
@@ -28,16 +143,10 @@ impl ContextManager {
         token_budget: Arc<TokenBudgetManager>,
         token_budget_enabled: bool,
     ) -> Self {
-        let (semantic_analyzer, semantic_score_cache) = if trim_config.semantic_compression {
-            match TreeSitterAnalyzer::new() {
-                Ok(analyzer) => (Some(analyzer), Some(HashMap::new())),
-                Err(error) => {
-                    warn!(
-                        error = %error,
-                        "Failed to initialize TreeSitterAnalyzer; disabling semantic compression"
-                    );
-                    (None, None)
-                }
+        let (semantic_scorer, semantic_score_cache) = if trim_config.semantic_compression {
+            match build_semantic_scorer(trim_config.semantic_scorer, &base_system_prompt) {
+                Some(scorer) => (Some(scorer), Some(HashMap::new())),
+                None => (None, None),
             }
         } else {
             (None, None)
@@ -48,7 +157,7 @@ impl ContextManager {
             token_budget,
             token_budget_enabled,
             base_system_prompt,
-            semantic_analyzer,
+            semantic_scorer,
             semantic_score_cache,
         }
     }
@@ -82,7 +191,7 @@ impl ContextManager {
         enforce_unified_context_window(
             history,
             self.trim_config,
-            self.semantic_analyzer.as_mut(),
+            self.semantic_scorer.as_deref_mut(),
             self.semantic_score_cache.as_mut(),
         )
     }
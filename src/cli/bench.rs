@@ -0,0 +1,17 @@
+use anyhow::Result;
+
+/// Delegate the `vtcode bench` command to the core implementation.
+pub async fn handle_bench_command(
+    command: vtcode_core::exec::benchmark::cli::BenchCommand,
+    sandbox_profile: vtcode_core::sandbox::SandboxProfile,
+    mcp_client: std::sync::Arc<dyn vtcode_core::mcp::McpToolExecutor>,
+    workspace_root: std::path::PathBuf,
+) -> Result<()> {
+    vtcode_core::exec::benchmark::cli::handle_bench_command(
+        command,
+        sandbox_profile,
+        mcp_client,
+        workspace_root,
+    )
+    .await
+}
@@ -0,0 +1,11 @@
+use anyhow::Result;
+
+/// Delegate the `vtcode --print system-prompt` command to the core
+/// implementation.
+pub async fn handle_print_system_prompt_command(
+    command: vtcode_core::prompts::system::cli::PrintSystemPromptCommand,
+    vtcode_config: Option<&vtcode_core::config::VTCodeConfig>,
+) -> Result<()> {
+    vtcode_core::prompts::system::cli::handle_print_system_prompt_command(command, vtcode_config)
+        .await
+}
@@ -1,12 +1,126 @@
 //! HTTP-based ACP client for agent communication
 
-use crate::discovery::AgentRegistry;
+use crate::discovery::{AgentRegistry, DiscoveryContext, DiscoveryGraph};
 use crate::error::{AcpError, AcpResult};
-use crate::messages::AcpMessage;
+use crate::messages::{AcpMessage, AcpResponse};
+use crate::signing::{self, SigningKey};
+use crate::transport::{
+    self, CompressionCodec, NegotiatedTransport, ReconnectPolicy, TransportHandshakeRequest,
+    TransportHandshakeResponse,
+};
 use reqwest::{Client as HttpClient, StatusCode};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::{debug, trace};
+use tokio::sync::{Mutex, mpsc, oneshot};
+use tracing::{debug, trace, warn};
+
+/// How often `await_response` falls back to polling `/responses/{id}` while
+/// waiting for a pushed response.
+const RESPONSE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Retry policy governing how many times, and with what backoff, a send is
+/// retried before a call gives up and surfaces its last error. Only errors
+/// `AcpError::is_retryable` permits are retried; permanent errors (e.g.
+/// `AgentNotFound`) fail immediately on the first attempt.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total send attempts, including the first, before giving up.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on each subsequent attempt.
+    pub base_delay: Duration,
+    /// Ceiling the doubling backoff is capped at.
+    pub max_delay: Duration,
+    /// Up to this fraction of the computed backoff (0.0-1.0) is added at
+    /// random on top of it, so many clients retrying the same flaky agent
+    /// don't all wake up in lockstep.
+    pub jitter_ratio: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            jitter_ratio: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff to wait before the `attempt`-th retry (1-indexed: the delay
+    /// before the *second* send attempt), doubling each time up to
+    /// `max_delay` and then randomizing within `jitter_ratio` of the result.
+    fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let scale = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        let backoff = self
+            .base_delay
+            .checked_mul(scale)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        if self.jitter_ratio <= 0.0 {
+            return backoff;
+        }
+        let jitter = (backoff.as_secs_f64() * self.jitter_ratio) * rand::random::<f64>();
+        backoff + Duration::from_secs_f64(jitter)
+    }
+}
+
+/// One failed attempt (or the final exhausted failure) reported on an
+/// [`AcpClient`]'s error channel, so operators get an aggregated,
+/// non-fatal stream of failures instead of only a hard per-call error.
+/// `attempt` is 1-indexed for failures reported mid-retry by the client
+/// itself; callers reporting a failure they didn't retry (e.g. an ACP tool
+/// surfacing the client's already-exhausted error to its caller) use `0`.
+#[derive(Debug, Clone)]
+pub struct AcpFailure {
+    pub agent_id: String,
+    pub action: String,
+    pub attempt: u32,
+    pub error: String,
+}
+
+/// Sink tools push [`AcpFailure`]s onto, drained by a background task that
+/// logs them. Cheap to clone; every clone shares the same underlying
+/// channel.
+#[derive(Clone)]
+pub struct ErrChan {
+    tx: mpsc::UnboundedSender<AcpFailure>,
+}
+
+impl ErrChan {
+    /// Report a failure. Never blocks; silently dropped if the draining
+    /// task has shut down.
+    pub fn report(&self, failure: AcpFailure) {
+        let _ = self.tx.send(failure);
+    }
+}
+
+fn action_of(message: &AcpMessage) -> String {
+    match &message.content {
+        crate::messages::MessageContent::Request(request) => request.action.clone(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Minimum ACP protocol version required to invoke a given action. Actions
+/// not listed here have no minimum (supported since `1.0.0`). Checked
+/// locally against the registry's cached `AgentInfo::protocol_version`
+/// before any network round-trip.
+const ACTION_MIN_VERSIONS: &[(&str, (u64, u64, u64))] = &[
+    ("stream_progress", (1, 1, 0)),
+    ("batch", (1, 2, 0)),
+];
+
+fn min_version_for_action(action: &str) -> Option<semver::Version> {
+    ACTION_MIN_VERSIONS
+        .iter()
+        .find(|(name, _)| *name == action)
+        .map(|(_, (major, minor, patch))| semver::Version::new(*major, *minor, *patch))
+}
 
 /// ACP Client for communicating with remote agents
 pub struct AcpClient {
@@ -22,12 +136,37 @@ pub struct AcpClient {
     /// Request timeout
     #[allow(dead_code)]
     timeout: Duration,
+
+    /// Key used to sign outgoing requests, if signing is enabled
+    signing_key: Option<SigningKey>,
+
+    /// Senders awaiting a response for a given message id, fulfilled by
+    /// `deliver_response` when a callback or poll resolves it
+    pending_responses: Arc<Mutex<HashMap<String, oneshot::Sender<AcpResponse>>>>,
+
+    /// Dedicated channel for structured call failures, drained by a
+    /// background task so reporting never blocks a caller. Also handed out
+    /// to callers (e.g. the ACP tools) via [`AcpClient::error_sink`] so they
+    /// can push their own failures onto the same aggregated stream.
+    error_chan: ErrChan,
+
+    /// Retry policy applied to `call_sync`/`call_async`/`ping`: how many
+    /// times, and with what backoff, a retryable failure is retried before
+    /// giving up.
+    retry_policy: RetryPolicy,
+
+    /// Backoff policy used when re-running the handshake with an agent that
+    /// was unreachable and has since recovered
+    reconnect_policy: ReconnectPolicy,
 }
 
 /// Builder for ACP client
 pub struct AcpClientBuilder {
     local_agent_id: String,
     timeout: Duration,
+    signing_key: Option<SigningKey>,
+    retry_policy: RetryPolicy,
+    reconnect_policy: ReconnectPolicy,
 }
 
 impl AcpClientBuilder {
@@ -36,6 +175,9 @@ impl AcpClientBuilder {
         Self {
             local_agent_id,
             timeout: Duration::from_secs(30),
+            signing_key: None,
+            retry_policy: RetryPolicy::default(),
+            reconnect_policy: ReconnectPolicy::default(),
         }
     }
 
@@ -45,15 +187,53 @@ impl AcpClientBuilder {
         self
     }
 
+    /// Sign every outgoing request with `key`. Opt-in: clients without a
+    /// signing key behave exactly as before.
+    pub fn with_signing_key(mut self, key: SigningKey) -> Self {
+        self.signing_key = Some(key);
+        self
+    }
+
+    /// Override the retry policy applied to `call_sync`/`call_async`/`ping`
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Override the backoff policy used when reconnecting to a previously
+    /// unreachable agent
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
     /// Build the client
     pub fn build(self) -> AcpResult<AcpClient> {
         let http_client = HttpClient::builder().timeout(self.timeout).build()?;
 
+        let (tx, mut rx) = mpsc::unbounded_channel::<AcpFailure>();
+        tokio::spawn(async move {
+            while let Some(failure) = rx.recv().await {
+                warn!(
+                    agent_id = %failure.agent_id,
+                    action = %failure.action,
+                    attempt = failure.attempt,
+                    error = %failure.error,
+                    "ACP call failed"
+                );
+            }
+        });
+
         Ok(AcpClient {
             http_client,
             local_agent_id: self.local_agent_id,
             registry: AgentRegistry::new(),
             timeout: self.timeout,
+            signing_key: self.signing_key,
+            pending_responses: Arc::new(Mutex::new(HashMap::new())),
+            error_chan: ErrChan { tx },
+            retry_policy: self.retry_policy,
+            reconnect_policy: self.reconnect_policy,
         })
     }
 }
@@ -69,6 +249,13 @@ impl AcpClient {
         &self.registry
     }
 
+    /// Clone of this client's structured failure sink. Callers outside the
+    /// client (e.g. the ACP tools) can push their own [`AcpFailure`]s onto
+    /// it so every failure ends up in the same aggregated, non-fatal stream.
+    pub fn error_sink(&self) -> ErrChan {
+        self.error_chan.clone()
+    }
+
     /// Send a request to a remote agent synchronously
     pub async fn call_sync(
         &self,
@@ -76,18 +263,14 @@ impl AcpClient {
         action: String,
         args: Value,
     ) -> AcpResult<Value> {
+        self.check_protocol_compatibility(remote_agent_id, &action).await?;
+
         debug!(
             remote_agent = remote_agent_id,
             action = %action,
             "Sending synchronous request to remote agent"
         );
 
-        let agent_info = self
-            .registry
-            .find(remote_agent_id)
-            .await
-            .map_err(|_| AcpError::AgentNotFound(remote_agent_id.to_string()))?;
-
         let message = AcpMessage::request(
             self.local_agent_id.clone(),
             remote_agent_id.to_string(),
@@ -95,7 +278,7 @@ impl AcpClient {
             args,
         );
 
-        let response = self.send_request(&agent_info.base_url, &message).await?;
+        let response = self.send_with_failover(remote_agent_id, &message).await?;
 
         trace!(
             remote_agent = remote_agent_id,
@@ -112,18 +295,14 @@ impl AcpClient {
         action: String,
         args: Value,
     ) -> AcpResult<String> {
+        self.check_protocol_compatibility(remote_agent_id, &action).await?;
+
         debug!(
             remote_agent = remote_agent_id,
             action = %action,
             "Sending asynchronous request to remote agent"
         );
 
-        let agent_info = self
-            .registry
-            .find(remote_agent_id)
-            .await
-            .map_err(|_| AcpError::AgentNotFound(remote_agent_id.to_string()))?;
-
         let mut message = AcpMessage::request(
             self.local_agent_id.clone(),
             remote_agent_id.to_string(),
@@ -136,8 +315,7 @@ impl AcpClient {
             req.sync = false;
         }
 
-        // Async calls may not wait for response
-        let _ = self.send_request(&agent_info.base_url, &message).await;
+        let _ = self.send_with_failover(remote_agent_id, &message).await;
 
         trace!(
             remote_agent = remote_agent_id,
@@ -148,13 +326,215 @@ impl AcpClient {
         Ok(message.id)
     }
 
-    /// Send raw ACP message and get response
+    /// Send a message, retrying retryable failures (per `self.retry_policy`
+    /// and `AcpError::is_retryable`) with jittered exponential backoff.
+    /// Permanent errors (e.g. `AgentNotFound`) fail on the first attempt.
+    /// Every failed attempt, and the final exhausted failure, is reported on
+    /// `self.error_chan` rather than dropped silently.
+    async fn send_with_retry(&self, base_url: &str, message: &AcpMessage) -> AcpResult<Value> {
+        let mut attempt = 0u32;
+
+        loop {
+            attempt += 1;
+            match self.send_request(base_url, message).await {
+                Ok(value) => return Ok(value),
+                Err(err) => {
+                    self.error_chan.report(AcpFailure {
+                        agent_id: message.recipient.clone(),
+                        action: action_of(message),
+                        attempt,
+                        error: err.to_string(),
+                    });
+
+                    if attempt >= self.retry_policy.max_attempts || !err.is_retryable() {
+                        return Err(err);
+                    }
+                    tokio::time::sleep(self.retry_policy.delay_for_attempt(attempt)).await;
+                }
+            }
+        }
+    }
+
+    /// Reject `action` locally, before any network round-trip, if
+    /// `remote_agent_id`'s cached `protocol_version` is below whatever
+    /// `ACTION_MIN_VERSIONS` requires for it. Actions with no listed minimum
+    /// always pass.
+    async fn check_protocol_compatibility(&self, remote_agent_id: &str, action: &str) -> AcpResult<()> {
+        let Some(required) = min_version_for_action(action) else {
+            return Ok(());
+        };
+
+        let agent = self.registry.find(remote_agent_id).await?;
+        if agent.protocol_version < required {
+            return Err(AcpError::IncompatibleVersion {
+                action: action.to_string(),
+                required,
+                remote: agent.protocol_version,
+            });
+        }
+        Ok(())
+    }
+
+    /// Resolve the endpoint to send the next request to for `remote_agent_id`,
+    /// refreshing the cached route from `/metadata` first if it's stale so a
+    /// recently-advertised `additional_endpoints` change is picked up.
+    async fn resolve_endpoint(&self, remote_agent_id: &str) -> AcpResult<String> {
+        if self.registry.route_is_stale(remote_agent_id).await {
+            let agent_info = self.registry.find(remote_agent_id).await?;
+            if let Ok(refreshed) = self.fetch_metadata(&agent_info.base_url).await {
+                self.registry.rebuild_route(remote_agent_id, &refreshed).await;
+                self.registry.register(refreshed).await.ok();
+            }
+        }
+
+        self.registry.select_endpoint(remote_agent_id).await
+    }
+
+    /// Send `message` to `remote_agent_id`, trying its cached endpoints in
+    /// turn (round-robin/least-recently-failed order) and transparently
+    /// failing over to the next one if a send fails, rather than surfacing
+    /// the first endpoint's error immediately.
+    async fn send_with_failover(&self, remote_agent_id: &str, message: &AcpMessage) -> AcpResult<Value> {
+        let mut endpoint = self.resolve_endpoint(remote_agent_id).await?;
+        let attempts = self.registry.endpoint_count(remote_agent_id).await.max(1);
+        let mut last_err = None;
+
+        for attempt in 0..attempts {
+            if attempt > 0 {
+                endpoint = self.registry.select_endpoint(remote_agent_id).await?;
+            }
+
+            match self.send_with_retry(&endpoint, message).await {
+                Ok(value) => {
+                    self.registry
+                        .record_endpoint_result(remote_agent_id, &endpoint, true)
+                        .await;
+                    return Ok(value);
+                }
+                Err(err) => {
+                    self.registry
+                        .record_endpoint_result(remote_agent_id, &endpoint, false)
+                        .await;
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| AcpError::AgentNotFound(remote_agent_id.to_string())))
+    }
+
+    /// Fulfill a pending `await_response` call for `message_id`, e.g. from a
+    /// callback endpoint the remote agent posts its response to. A no-op if
+    /// nothing is currently awaiting that message.
+    pub async fn deliver_response(&self, message_id: &str, response: AcpResponse) {
+        if let Some(sender) = self.pending_responses.lock().await.remove(message_id) {
+            let _ = sender.send(response);
+        }
+    }
+
+    /// Wait for the eventual response to a message sent via `call_async`.
+    /// Resolves as soon as either `deliver_response` is called for this
+    /// message id, or a poll of `{base_url}/responses/{message_id}` on
+    /// `remote_agent_id` succeeds — whichever comes first. Returns
+    /// `AcpError::Timeout` if neither happens within `timeout`.
+    pub async fn await_response(
+        &self,
+        message_id: &str,
+        remote_agent_id: &str,
+        timeout: Duration,
+    ) -> AcpResult<AcpResponse> {
+        let (tx, rx) = oneshot::channel();
+        self.pending_responses
+            .lock()
+            .await
+            .insert(message_id.to_string(), tx);
+
+        let agent_info = self
+            .registry
+            .find(remote_agent_id)
+            .await
+            .map_err(|_| AcpError::AgentNotFound(remote_agent_id.to_string()))?;
+        let poll_url = format!(
+            "{}/responses/{}",
+            agent_info.base_url.trim_end_matches('/'),
+            message_id
+        );
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        tokio::pin!(rx);
+
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                self.pending_responses.lock().await.remove(message_id);
+                return Err(AcpError::Timeout(format!(
+                    "No response for message {message_id} within timeout"
+                )));
+            }
+
+            let sleep_for = RESPONSE_POLL_INTERVAL.min(remaining);
+
+            tokio::select! {
+                result = &mut rx => {
+                    return result.map_err(|_| {
+                        AcpError::Internal("response channel closed unexpectedly".to_string())
+                    });
+                }
+                _ = tokio::time::sleep(sleep_for) => {
+                    if let Ok(response) = self.http_client.get(&poll_url).send().await {
+                        if response.status().is_success() {
+                            if let Ok(acp_response) = response.json::<AcpResponse>().await {
+                                self.pending_responses.lock().await.remove(message_id);
+                                return Ok(acp_response);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Send raw ACP message and get response, compressing the body with
+    /// whatever codec was negotiated with this agent during the transport
+    /// handshake (`CompressionCodec::Identity` if none was negotiated).
     async fn send_request(&self, base_url: &str, message: &AcpMessage) -> AcpResult<Value> {
         let url = format!("{}/messages", base_url.trim_end_matches('/'));
 
         trace!(url = %url, message_id = %message.id, "Sending ACP message");
 
-        let response = self.http_client.post(&url).json(message).send().await?;
+        let codec = self
+            .registry
+            .negotiated_transport(&message.recipient)
+            .await
+            .map(|transport| transport.codec)
+            .unwrap_or(CompressionCodec::Identity);
+
+        let json_body = serde_json::to_vec(message)?;
+        let wire_body = codec.compress(&json_body)?;
+
+        let mut request = self.http_client.post(&url).header("Content-Type", "application/json");
+        if let Some(encoding) = codec.content_encoding() {
+            request = request.header("Content-Encoding", encoding);
+        }
+
+        if let Some(signing_key) = &self.signing_key {
+            let host = reqwest::Url::parse(&url)
+                .ok()
+                .and_then(|parsed| parsed.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let date = chrono::Utc::now().to_rfc2822();
+            let headers =
+                signing::sign_request(signing_key, "post /messages", &host, &date, &wire_body);
+
+            request = request
+                .header("Digest", headers.digest)
+                .header("Date", headers.date)
+                .header("Signature", headers.signature);
+        }
+
+        request = request.body(wire_body);
+
+        let response = request.send().await?;
 
         let status = response.status();
 
@@ -198,8 +578,42 @@ impl AcpClient {
         }
     }
 
-    /// Discover agent metadata from base URL (offline discovery)
+    /// Discover agent metadata from base URL (offline discovery).
+    ///
+    /// Validates that the fetched `AgentInfo.base_url` actually matches the
+    /// URL we requested. If an endpoint advertises a different canonical
+    /// base URL (e.g. it redirected us to a mirror), we refetch exactly once
+    /// from that canonical URL; if it still disagrees, discovery fails with
+    /// `AcpError::IdentityMismatch` rather than trusting a possibly
+    /// misconfigured or malicious endpoint.
     pub async fn discover_agent(&self, base_url: &str) -> AcpResult<crate::discovery::AgentInfo> {
+        let agent_info = self.fetch_metadata(base_url).await?;
+
+        if urls_match(base_url, &agent_info.base_url) {
+            trace!("Agent metadata discovered successfully");
+            return Ok(agent_info);
+        }
+
+        trace!(
+            requested = base_url,
+            advertised = %agent_info.base_url,
+            "Agent advertised a different base URL; refetching from canonical URL"
+        );
+
+        let canonical_info = self.fetch_metadata(&agent_info.base_url).await?;
+
+        if urls_match(&agent_info.base_url, &canonical_info.base_url) {
+            trace!("Agent metadata discovered successfully after refetch");
+            Ok(canonical_info)
+        } else {
+            Err(AcpError::IdentityMismatch {
+                requested: base_url.to_string(),
+                advertised: canonical_info.base_url,
+            })
+        }
+    }
+
+    async fn fetch_metadata(&self, base_url: &str) -> AcpResult<crate::discovery::AgentInfo> {
         let url = format!("{}/metadata", base_url.trim_end_matches('/'));
 
         trace!(url = %url, "Discovering agent metadata");
@@ -218,14 +632,113 @@ impl AcpClient {
             )));
         }
 
-        let agent_info = response.json().await?;
+        Ok(response.json().await?)
+    }
+
+    /// Discover an agent plus the peers it references in its metadata,
+    /// walking the graph iteratively (not recursively) so a deeply nested or
+    /// cyclic peer chain can't overflow the stack. Stops expanding once
+    /// `context.max_depth` or `context.max_fetches` is hit and returns the
+    /// partial graph with `truncated` set, rather than erroring.
+    pub async fn discover_agent_graph(
+        &self,
+        base_url: &str,
+        context: DiscoveryContext,
+    ) -> DiscoveryGraph {
+        let mut graph = DiscoveryGraph::default();
+        let mut visited: std::collections::HashSet<String> = std::collections::HashSet::new();
+        let mut queue: std::collections::VecDeque<(String, usize)> =
+            std::collections::VecDeque::new();
+        queue.push_back((base_url.to_string(), 0));
+        visited.insert(base_url.to_string());
+
+        let mut fetches = 0usize;
+
+        while let Some((url, depth)) = queue.pop_front() {
+            if fetches >= context.max_fetches {
+                graph.truncated = true;
+                break;
+            }
+            if depth > context.max_depth {
+                graph.truncated = true;
+                continue;
+            }
+
+            fetches += 1;
+            let agent_info = match self.discover_agent(&url).await {
+                Ok(info) => info,
+                Err(_) => continue,
+            };
+
+            for peer_url in &agent_info.known_peers {
+                if visited.insert(peer_url.clone()) {
+                    queue.push_back((peer_url.clone(), depth + 1));
+                }
+            }
+
+            graph.agents.push(agent_info);
+        }
+
+        if !queue.is_empty() {
+            graph.truncated = true;
+        }
+
+        graph
+    }
+
+    /// Negotiate transport options (compression codec, keep-alive) with an
+    /// agent and cache the result in the registry for subsequent
+    /// `send_request` calls.
+    pub async fn negotiate_transport(&self, remote_agent_id: &str) -> AcpResult<NegotiatedTransport> {
+        let agent_info = self
+            .registry
+            .find(remote_agent_id)
+            .await
+            .map_err(|_| AcpError::AgentNotFound(remote_agent_id.to_string()))?;
+
+        let url = format!(
+            "{}/transport-handshake",
+            agent_info.base_url.trim_end_matches('/')
+        );
+        let request = TransportHandshakeRequest {
+            supported_codecs: transport::supported_codecs(),
+            keep_alive_secs: 30,
+        };
+
+        let response = self
+            .http_client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|err| AcpError::NetworkError(format!("Transport handshake failed: {err}")))?;
+
+        if !response.status().is_success() {
+            return Err(AcpError::NetworkError(format!(
+                "Transport handshake failed with status {}",
+                response.status()
+            )));
+        }
+
+        let handshake: TransportHandshakeResponse = response.json().await?;
+        let negotiated = NegotiatedTransport {
+            codec: handshake.codec,
+            keep_alive: Duration::from_secs(handshake.keep_alive_secs),
+        };
 
-        trace!("Agent metadata discovered successfully");
+        self.registry
+            .set_negotiated_transport(remote_agent_id, negotiated)
+            .await;
 
-        Ok(agent_info)
+        Ok(negotiated)
     }
 
-    /// Check if a remote agent is reachable
+    /// Check if a remote agent is reachable. If an agent that was previously
+    /// marked unreachable responds again, the transport handshake is
+    /// automatically re-run (after a backoff governed by the client's
+    /// `ReconnectPolicy`) before the agent is marked healthy, so a flapping
+    /// peer can't skip straight back to serving stale negotiated transport
+    /// state.
     pub async fn ping(&self, remote_agent_id: &str) -> AcpResult<bool> {
         let agent_info = self
             .registry
@@ -233,30 +746,95 @@ impl AcpClient {
             .await
             .map_err(|_| AcpError::AgentNotFound(remote_agent_id.to_string()))?;
 
-        let url = format!("{}/health", agent_info.base_url.trim_end_matches('/'));
+        let was_unreachable = !agent_info.online;
+        self.registry.rebuild_route(remote_agent_id, &agent_info).await;
 
-        match self.http_client.get(&url).send().await {
-            Ok(response) => {
-                let is_healthy = response.status().is_success();
-                if is_healthy {
-                    self.registry
-                        .update_status(remote_agent_id, true)
-                        .await
-                        .ok();
+        let mut endpoints = vec![agent_info.base_url.clone()];
+        endpoints.extend(agent_info.additional_endpoints.iter().cloned());
+
+        let mut any_healthy = false;
+        for endpoint in &endpoints {
+            let is_healthy = self.ping_endpoint_with_retry(remote_agent_id, endpoint).await;
+            self.registry
+                .record_endpoint_result(remote_agent_id, endpoint, is_healthy)
+                .await;
+            any_healthy |= is_healthy;
+        }
+
+        if any_healthy && was_unreachable {
+            self.reconnect(remote_agent_id).await;
+        }
+
+        self.registry
+            .update_status(remote_agent_id, any_healthy)
+            .await
+            .ok();
+
+        Ok(any_healthy)
+    }
+
+    /// `GET {endpoint}/health`, retrying transient failures per
+    /// `self.retry_policy` before reporting the endpoint unhealthy. Each
+    /// failed attempt is reported on `self.error_chan`.
+    async fn ping_endpoint_with_retry(&self, remote_agent_id: &str, endpoint: &str) -> bool {
+        let url = format!("{}/health", endpoint.trim_end_matches('/'));
+        let mut attempt = 0u32;
+
+        loop {
+            attempt += 1;
+            let outcome = self.http_client.get(&url).send().await;
+            match outcome {
+                Ok(response) if response.status().is_success() => return true,
+                result => {
+                    let error = match result {
+                        Ok(response) => format!("health check returned {}", response.status()),
+                        Err(err) => err.to_string(),
+                    };
+                    self.error_chan.report(AcpFailure {
+                        agent_id: remote_agent_id.to_string(),
+                        action: "ping".to_string(),
+                        attempt,
+                        error,
+                    });
+                    if attempt >= self.retry_policy.max_attempts {
+                        return false;
+                    }
+                    tokio::time::sleep(self.retry_policy.delay_for_attempt(attempt)).await;
                 }
-                Ok(is_healthy)
             }
-            Err(_) => {
-                self.registry
-                    .update_status(remote_agent_id, false)
-                    .await
-                    .ok();
-                Ok(false)
+        }
+    }
+
+    /// Re-run the transport handshake with a recovered agent, retrying with
+    /// the configured backoff if the handshake itself is still flaky.
+    async fn reconnect(&self, remote_agent_id: &str) {
+        const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+
+        for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+            tokio::time::sleep(self.reconnect_policy.backoff_for_attempt(attempt)).await;
+
+            if self.negotiate_transport(remote_agent_id).await.is_ok() {
+                trace!(
+                    remote_agent = remote_agent_id,
+                    attempt, "Reconnect handshake succeeded"
+                );
+                return;
             }
         }
+
+        warn!(
+            remote_agent = remote_agent_id,
+            "Reconnect handshake failed after all attempts"
+        );
     }
 }
 
+/// Compare two base URLs ignoring a trailing slash, since
+/// `http://agent/` and `http://agent` refer to the same endpoint.
+fn urls_match(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -277,4 +855,10 @@ mod tests {
         assert_eq!(client.local_agent_id, "test-agent");
         assert_eq!(client.timeout, Duration::from_secs(60));
     }
+
+    #[test]
+    fn test_urls_match_ignores_trailing_slash() {
+        assert!(urls_match("http://agent.local", "http://agent.local/"));
+        assert!(!urls_match("http://agent.local", "http://other.local"));
+    }
 }
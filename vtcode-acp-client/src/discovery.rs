@@ -5,7 +5,24 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+/// How long a cached `Route` is trusted before it's refreshed from `/metadata`.
+const ROUTE_TTL: Duration = Duration::from_secs(60);
+
+/// Default `heartbeat_interval_secs` for agents that don't advertise one.
+const DEFAULT_HEARTBEAT_INTERVAL_SECS: u64 = 30;
+
+fn default_heartbeat_interval_secs() -> u64 {
+    DEFAULT_HEARTBEAT_INTERVAL_SECS
+}
+
+fn default_protocol_version() -> semver::Version {
+    semver::Version::new(1, 0, 0)
+}
 
 /// Information about a registered agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,16 +52,206 @@ pub struct AgentInfo {
 
     /// Last heartbeat/update timestamp
     pub last_seen: Option<String>,
+
+    /// Base64-encoded Ed25519 public key used to verify signed requests from
+    /// this agent, advertised in its `/metadata` response.
+    #[serde(default)]
+    pub public_key: Option<String>,
+
+    /// Base URLs of peer agents this agent knows about, used to expand a
+    /// discovery graph beyond a single endpoint.
+    #[serde(default)]
+    pub known_peers: Vec<String>,
+
+    /// Additional endpoint URLs this same logical agent can also be reached
+    /// at, for routing and failover. `base_url` is always implicitly a
+    /// member of the route.
+    #[serde(default)]
+    pub additional_endpoints: Vec<String>,
+
+    /// How often this agent is expected to send a heartbeat. Drives
+    /// [`AgentRegistry::start_reaper`]'s staleness threshold for this agent.
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+
+    /// ACP protocol version this agent speaks, advertised in its `/metadata`
+    /// response. Older peers that predate this field default to `1.0.0`.
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: semver::Version,
 }
 
 fn default_online() -> bool {
     true
 }
 
+/// Bounds on a recursive discovery walk, so a malicious or misconfigured
+/// agent can't reference peers deeply enough to overflow the stack or issue
+/// unbounded requests.
+#[derive(Debug, Clone, Copy)]
+pub struct DiscoveryContext {
+    /// How many hops away from the starting agent to follow `known_peers`
+    pub max_depth: usize,
+
+    /// Total number of agents to fetch metadata for across the whole walk
+    /// (each may take a second `/metadata` request if it needs a refetch)
+    pub max_fetches: usize,
+}
+
+impl DiscoveryContext {
+    pub fn new(max_depth: usize, max_fetches: usize) -> Self {
+        Self {
+            max_depth,
+            max_fetches,
+        }
+    }
+}
+
+impl Default for DiscoveryContext {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_fetches: 25,
+        }
+    }
+}
+
+/// Health of one endpoint within a `Route`.
+#[derive(Debug, Clone)]
+struct EndpointHealth {
+    url: String,
+    healthy: bool,
+    last_failure: Option<Instant>,
+}
+
+/// A cached set of endpoints for one logical agent, used to round-robin (or
+/// fail over) between multiple copies of the same agent instead of always
+/// hitting its `base_url`.
+#[derive(Debug, Clone)]
+struct Route {
+    endpoints: Vec<EndpointHealth>,
+    fetched_at: Instant,
+    next_index: usize,
+}
+
+impl Route {
+    /// Build a route from an agent's `base_url` plus any
+    /// `additional_endpoints`, deduplicating the latter against the former.
+    fn new(base_url: &str, additional_endpoints: &[String]) -> Self {
+        let mut endpoints = vec![EndpointHealth {
+            url: base_url.to_string(),
+            healthy: true,
+            last_failure: None,
+        }];
+
+        for endpoint in additional_endpoints {
+            if endpoint != base_url {
+                endpoints.push(EndpointHealth {
+                    url: endpoint.clone(),
+                    healthy: true,
+                    last_failure: None,
+                });
+            }
+        }
+
+        Self {
+            endpoints,
+            fetched_at: Instant::now(),
+            next_index: 0,
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        self.fetched_at.elapsed() >= ROUTE_TTL
+    }
+
+    /// Pick the next endpoint to try: round-robin among currently-healthy
+    /// endpoints, or — if every endpoint is marked unhealthy — the one that
+    /// failed least recently, so a fully-down agent still gets retried
+    /// somewhere rather than refusing to select at all.
+    fn select(&mut self) -> String {
+        if self.endpoints.iter().all(|e| !e.healthy) {
+            return self
+                .endpoints
+                .iter()
+                .min_by_key(|e| e.last_failure.unwrap_or_else(Instant::now))
+                .map(|e| e.url.clone())
+                .unwrap_or_default();
+        }
+
+        loop {
+            let idx = self.next_index % self.endpoints.len();
+            self.next_index = self.next_index.wrapping_add(1);
+            if self.endpoints[idx].healthy {
+                return self.endpoints[idx].url.clone();
+            }
+        }
+    }
+
+    fn mark_success(&mut self, url: &str) {
+        if let Some(endpoint) = self.endpoints.iter_mut().find(|e| e.url == url) {
+            endpoint.healthy = true;
+            endpoint.last_failure = None;
+        }
+    }
+
+    fn mark_failure(&mut self, url: &str) {
+        if let Some(endpoint) = self.endpoints.iter_mut().find(|e| e.url == url) {
+            endpoint.healthy = false;
+            endpoint.last_failure = Some(Instant::now());
+        }
+    }
+}
+
+/// Result of walking an agent's peer graph starting from one base URL.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryGraph {
+    /// Every agent successfully discovered, in visitation order
+    pub agents: Vec<AgentInfo>,
+
+    /// Set if the walk stopped early because it hit `max_depth` or
+    /// `max_fetches` rather than exhausting every reachable peer
+    pub truncated: bool,
+}
+
+/// Highest mutually-supported protocol version and intersected capability
+/// set between us and a registered agent, computed locally from the
+/// registry's cached `AgentInfo` so callers can check compatibility without
+/// a network round-trip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegotiatedProtocol {
+    pub version: semver::Version,
+    pub capabilities: Vec<String>,
+}
+
+/// Handle to a running [`AgentRegistry::start_reaper`] task. Dropping it
+/// stops the reaper; callers that want it to outlive the handle should
+/// `std::mem::forget` it or keep it alive alongside the registry.
+pub struct ReaperHandle {
+    shutdown: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
+impl ReaperHandle {
+    /// Signal the reaper to stop after its current sweep, without waiting
+    /// for it to actually exit.
+    pub fn stop(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ReaperHandle {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        self.task.abort();
+    }
+}
+
 /// Agent registry for discovery and lookup
 #[derive(Clone)]
 pub struct AgentRegistry {
     agents: Arc<RwLock<HashMap<String, AgentInfo>>>,
+    negotiated_transports: Arc<RwLock<HashMap<String, crate::transport::NegotiatedTransport>>>,
+    routes: Arc<RwLock<HashMap<String, Route>>>,
 }
 
 impl AgentRegistry {
@@ -52,9 +259,31 @@ impl AgentRegistry {
     pub fn new() -> Self {
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
+            negotiated_transports: Arc::new(RwLock::new(HashMap::new())),
+            routes: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Cache the transport negotiated with an agent after a handshake
+    pub async fn set_negotiated_transport(
+        &self,
+        agent_id: &str,
+        transport: crate::transport::NegotiatedTransport,
+    ) {
+        self.negotiated_transports
+            .write()
+            .await
+            .insert(agent_id.to_string(), transport);
+    }
+
+    /// Look up the transport previously negotiated with an agent, if any
+    pub async fn negotiated_transport(
+        &self,
+        agent_id: &str,
+    ) -> Option<crate::transport::NegotiatedTransport> {
+        self.negotiated_transports.read().await.get(agent_id).copied()
+    }
+
     /// Register an agent
     pub async fn register(&self, agent: AgentInfo) -> AcpResult<()> {
         let mut agents = self.agents.write().await;
@@ -113,6 +342,178 @@ impl AgentRegistry {
         }
     }
 
+    /// Refresh `agent_id`'s `last_seen` to now and mark it online, e.g. in
+    /// response to a periodic heartbeat ping from the agent. Does not touch
+    /// `heartbeat_interval_secs`.
+    pub async fn heartbeat(&self, agent_id: &str) -> AcpResult<()> {
+        let mut agents = self.agents.write().await;
+        let agent = agents
+            .get_mut(agent_id)
+            .ok_or_else(|| AcpError::AgentNotFound(agent_id.to_string()))?;
+        agent.last_seen = Some(chrono::Utc::now().to_rfc3339());
+        agent.online = true;
+        Ok(())
+    }
+
+    /// Spawn a background task that periodically sweeps all registered
+    /// agents and flips `online = false` for any whose `last_seen` is older
+    /// than `ttl_multiplier * heartbeat_interval_secs`. An agent with no
+    /// `last_seen` yet (never heartbeated since registration) is left alone.
+    ///
+    /// The sweep interval is the shortest `heartbeat_interval_secs` across
+    /// all agents (or `DEFAULT_HEARTBEAT_INTERVAL_SECS` if the registry is
+    /// empty), re-evaluated every sweep so newly-registered agents with a
+    /// shorter interval are picked up. The returned [`ReaperHandle`] stops
+    /// the task when dropped.
+    pub fn start_reaper(&self, ttl_multiplier: u32) -> ReaperHandle {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let registry = self.clone();
+        let task_shutdown = shutdown.clone();
+        let task = tokio::spawn(async move {
+            while !task_shutdown.load(Ordering::Relaxed) {
+                let sweep_interval = registry.shortest_heartbeat_interval().await;
+                tokio::time::sleep(Duration::from_secs(sweep_interval)).await;
+                if task_shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
+                registry.reap_stale_agents(ttl_multiplier).await;
+            }
+        });
+        ReaperHandle { shutdown, task }
+    }
+
+    async fn shortest_heartbeat_interval(&self) -> u64 {
+        self.agents
+            .read()
+            .await
+            .values()
+            .map(|agent| agent.heartbeat_interval_secs)
+            .min()
+            .unwrap_or(DEFAULT_HEARTBEAT_INTERVAL_SECS)
+    }
+
+    /// One sweep: parse every agent's RFC3339 `last_seen` and mark it
+    /// offline if older than `ttl_multiplier * heartbeat_interval_secs`.
+    /// Holds the write lock only for the duration of the scan itself.
+    async fn reap_stale_agents(&self, ttl_multiplier: u32) {
+        let now = chrono::Utc::now();
+        let mut agents = self.agents.write().await;
+        for agent in agents.values_mut() {
+            if !agent.online {
+                continue;
+            }
+            let Some(last_seen) = agent.last_seen.as_deref() else {
+                continue;
+            };
+            let Ok(last_seen) = chrono::DateTime::parse_from_rfc3339(last_seen) else {
+                continue;
+            };
+            let ttl = Duration::from_secs(agent.heartbeat_interval_secs * ttl_multiplier as u64);
+            let age = now.signed_duration_since(last_seen).to_std().unwrap_or(Duration::ZERO);
+            if age >= ttl {
+                agent.online = false;
+            }
+        }
+    }
+
+    /// Compute the highest mutually-supported protocol version and the
+    /// intersection of capabilities between `our_version`/`our_capabilities`
+    /// and `agent_id`'s advertised `protocol_version`/`capabilities`, purely
+    /// from the registry cache.
+    pub async fn negotiate(
+        &self,
+        agent_id: &str,
+        our_version: &semver::Version,
+        our_capabilities: &[String],
+    ) -> AcpResult<NegotiatedProtocol> {
+        let agent = self.find(agent_id).await?;
+        let version = our_version.min(&agent.protocol_version).clone();
+        let capabilities = our_capabilities
+            .iter()
+            .filter(|capability| agent.capabilities.contains(capability))
+            .cloned()
+            .collect();
+        Ok(NegotiatedProtocol { version, capabilities })
+    }
+
+    /// Look up the cached public key for a registered agent, for verifying
+    /// signed requests it claims to have sent.
+    pub async fn verify_key(&self, agent_id: &str) -> AcpResult<Option<crate::signing::VerifyKey>> {
+        let agents = self.agents.read().await;
+        let agent = agents
+            .get(agent_id)
+            .ok_or_else(|| AcpError::AgentNotFound(agent_id.to_string()))?;
+
+        agent
+            .public_key
+            .as_deref()
+            .map(crate::signing::VerifyKey::from_base64)
+            .transpose()
+    }
+
+    /// Whether `agent_id`'s cached route is missing or older than
+    /// `ROUTE_TTL`, and should be refreshed (e.g. from `/metadata`) before
+    /// the next endpoint selection.
+    pub async fn route_is_stale(&self, agent_id: &str) -> bool {
+        self.routes
+            .read()
+            .await
+            .get(agent_id)
+            .map(|route| route.is_stale())
+            .unwrap_or(true)
+    }
+
+    /// Build the cached route for `agent_id` from `agent`'s `base_url` and
+    /// `additional_endpoints`, unconditionally replacing any existing route.
+    /// Called after a fresh `/metadata` fetch to pick up endpoint changes.
+    pub async fn rebuild_route(&self, agent_id: &str, agent: &AgentInfo) {
+        self.routes.write().await.insert(
+            agent_id.to_string(),
+            Route::new(&agent.base_url, &agent.additional_endpoints),
+        );
+    }
+
+    /// Select the next endpoint to try for `agent_id`, building its route
+    /// from the registered `AgentInfo` first if one doesn't exist yet.
+    /// Round-robins among healthy endpoints, falling back to the
+    /// least-recently-failed endpoint if none are currently healthy.
+    pub async fn select_endpoint(&self, agent_id: &str) -> AcpResult<String> {
+        if !self.routes.read().await.contains_key(agent_id) {
+            let agent = self.find(agent_id).await?;
+            self.rebuild_route(agent_id, &agent).await;
+        }
+
+        let mut routes = self.routes.write().await;
+        let route = routes
+            .get_mut(agent_id)
+            .ok_or_else(|| AcpError::AgentNotFound(agent_id.to_string()))?;
+        Ok(route.select())
+    }
+
+    /// Number of endpoints currently cached for `agent_id`'s route, or 1 if
+    /// no route has been built yet.
+    pub async fn endpoint_count(&self, agent_id: &str) -> usize {
+        self.routes
+            .read()
+            .await
+            .get(agent_id)
+            .map(|route| route.endpoints.len())
+            .unwrap_or(1)
+    }
+
+    /// Record the outcome of a request sent to one endpoint of `agent_id`'s
+    /// route, so future selection can skip unhealthy endpoints. A no-op if
+    /// the route hasn't been built yet.
+    pub async fn record_endpoint_result(&self, agent_id: &str, endpoint: &str, healthy: bool) {
+        if let Some(route) = self.routes.write().await.get_mut(agent_id) {
+            if healthy {
+                route.mark_success(endpoint);
+            } else {
+                route.mark_failure(endpoint);
+            }
+        }
+    }
+
     /// Get agent count
     pub async fn count(&self) -> usize {
         self.agents.read().await.len()
@@ -147,6 +548,11 @@ mod tests {
             metadata: HashMap::new(),
             online: true,
             last_seen: None,
+            public_key: None,
+            known_peers: Vec::new(),
+            additional_endpoints: Vec::new(),
+            heartbeat_interval_secs: 30,
+            protocol_version: semver::Version::new(1, 0, 0),
         };
 
         registry.register(agent.clone()).await.unwrap();
@@ -173,6 +579,11 @@ mod tests {
             metadata: HashMap::new(),
             online: true,
             last_seen: None,
+            public_key: None,
+            known_peers: Vec::new(),
+            additional_endpoints: Vec::new(),
+            heartbeat_interval_secs: 30,
+            protocol_version: semver::Version::new(1, 0, 0),
         };
 
         let agent2 = AgentInfo {
@@ -184,6 +595,11 @@ mod tests {
             metadata: HashMap::new(),
             online: true,
             last_seen: None,
+            public_key: None,
+            known_peers: Vec::new(),
+            additional_endpoints: Vec::new(),
+            heartbeat_interval_secs: 30,
+            protocol_version: semver::Version::new(1, 0, 0),
         };
 
         registry.register(agent1).await.unwrap();
@@ -195,4 +611,125 @@ mod tests {
         let python_agents = registry.find_by_capability("python").await.unwrap();
         assert_eq!(python_agents.len(), 1);
     }
+
+    fn agent_with_endpoints(id: &str, base_url: &str, additional: Vec<String>) -> AgentInfo {
+        AgentInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            base_url: base_url.to_string(),
+            description: None,
+            capabilities: Vec::new(),
+            metadata: HashMap::new(),
+            online: true,
+            last_seen: None,
+            public_key: None,
+            known_peers: Vec::new(),
+            additional_endpoints: additional,
+            heartbeat_interval_secs: 30,
+            protocol_version: semver::Version::new(1, 0, 0),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_select_endpoint_round_robins() {
+        let registry = AgentRegistry::new();
+        let agent = agent_with_endpoints(
+            "multi",
+            "http://a",
+            vec!["http://b".to_string(), "http://c".to_string()],
+        );
+        registry.register(agent).await.unwrap();
+
+        let first = registry.select_endpoint("multi").await.unwrap();
+        let second = registry.select_endpoint("multi").await.unwrap();
+        let third = registry.select_endpoint("multi").await.unwrap();
+        let fourth = registry.select_endpoint("multi").await.unwrap();
+
+        assert_eq!(vec![first, second, third], vec!["http://a", "http://b", "http://c"]);
+        assert_eq!(fourth, "http://a");
+    }
+
+    #[tokio::test]
+    async fn test_select_endpoint_skips_unhealthy() {
+        let registry = AgentRegistry::new();
+        let agent = agent_with_endpoints("multi", "http://a", vec!["http://b".to_string()]);
+        registry.register(agent).await.unwrap();
+
+        // Prime the route, then mark "http://a" unhealthy.
+        registry.select_endpoint("multi").await.unwrap();
+        registry
+            .record_endpoint_result("multi", "http://a", false)
+            .await;
+
+        for _ in 0..4 {
+            assert_eq!(
+                registry.select_endpoint("multi").await.unwrap(),
+                "http://b"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_select_endpoint_falls_back_when_all_unhealthy() {
+        let registry = AgentRegistry::new();
+        let agent = agent_with_endpoints("multi", "http://a", vec!["http://b".to_string()]);
+        registry.register(agent).await.unwrap();
+
+        registry.select_endpoint("multi").await.unwrap();
+        registry
+            .record_endpoint_result("multi", "http://a", false)
+            .await;
+        registry
+            .record_endpoint_result("multi", "http://b", false)
+            .await;
+
+        // Neither endpoint is healthy, but selection must still return one.
+        let selected = registry.select_endpoint("multi").await.unwrap();
+        assert!(selected == "http://a" || selected == "http://b");
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_refreshes_last_seen_and_online() {
+        let registry = AgentRegistry::new();
+        let mut agent = agent_with_endpoints("hb", "http://a", Vec::new());
+        agent.online = false;
+        agent.last_seen = None;
+        registry.register(agent).await.unwrap();
+
+        registry.heartbeat("hb").await.unwrap();
+
+        let found = registry.find("hb").await.unwrap();
+        assert!(found.online);
+        assert!(found.last_seen.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_unknown_agent_errors() {
+        let registry = AgentRegistry::new();
+        assert!(matches!(
+            registry.heartbeat("missing").await,
+            Err(AcpError::AgentNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reap_stale_agents_marks_offline() {
+        let registry = AgentRegistry::new();
+        let mut stale = agent_with_endpoints("stale", "http://a", Vec::new());
+        stale.heartbeat_interval_secs = 1;
+        stale.last_seen = Some(
+            (chrono::Utc::now() - chrono::Duration::seconds(10)).to_rfc3339(),
+        );
+        registry.register(stale).await.unwrap();
+
+        let mut fresh = agent_with_endpoints("fresh", "http://b", Vec::new());
+        fresh.heartbeat_interval_secs = 30;
+        fresh.last_seen = Some(chrono::Utc::now().to_rfc3339());
+        registry.register(fresh).await.unwrap();
+
+        registry.reap_stale_agents(3).await;
+
+        assert!(!registry.find("stale").await.unwrap().online);
+        assert!(registry.find("fresh").await.unwrap().online);
+    }
 }
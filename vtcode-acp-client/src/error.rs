@@ -33,8 +33,41 @@ pub enum AcpError {
     /// Configuration error
     ConfigError(String),
 
+    /// Peer advertised or required a protocol version we can't speak
+    UnsupportedProtocolVersion { ours: u32, theirs: u32 },
+
+    /// Rejected locally (no network round-trip) because the remote agent's
+    /// cached `protocol_version` is below what the requested action requires
+    IncompatibleVersion {
+        action: String,
+        required: semver::Version,
+        remote: semver::Version,
+    },
+
+    /// Attempted to use a capability the peer never advertised during handshake
+    CapabilityNotNegotiated(String),
+
+    /// Message signing or signature verification failed
+    SignatureError(String),
+
+    /// An incoming request's `Signature`/`Digest` failed verification
+    /// against the sender's registered public key, or its `Date` header was
+    /// outside the configured replay-protection skew window
+    SignatureInvalid(String),
+
+    /// A `/metadata` response's advertised identity didn't match the URL it
+    /// was requested from, even after a single refetch from the canonical URL
+    IdentityMismatch { requested: String, advertised: String },
+
     /// Generic internal error
     Internal(String),
+
+    /// A call was rejected because it would exceed the session's cost budget
+    BudgetExceeded {
+        spent: f64,
+        budget: f64,
+        attempted: f64,
+    },
 }
 
 impl fmt::Display for AcpError {
@@ -57,11 +90,62 @@ impl fmt::Display for AcpError {
             }
             AcpError::Timeout(e) => write!(f, "Timeout: {}", e),
             AcpError::ConfigError(e) => write!(f, "Configuration error: {}", e),
+            AcpError::UnsupportedProtocolVersion { ours, theirs } => write!(
+                f,
+                "Unsupported protocol version: peer speaks {} but we understand up to {}",
+                theirs, ours
+            ),
+            AcpError::IncompatibleVersion {
+                action,
+                required,
+                remote,
+            } => write!(
+                f,
+                "Action '{}' requires protocol version {} but remote agent advertises {}",
+                action, required, remote
+            ),
+            AcpError::CapabilityNotNegotiated(cap) => write!(
+                f,
+                "Capability '{}' was not negotiated with the peer",
+                cap
+            ),
+            AcpError::SignatureError(e) => write!(f, "Signature error: {}", e),
+            AcpError::SignatureInvalid(e) => write!(f, "Invalid request signature: {}", e),
+            AcpError::IdentityMismatch {
+                requested,
+                advertised,
+            } => write!(
+                f,
+                "Agent identity mismatch: requested {} but metadata advertised {}",
+                requested, advertised
+            ),
             AcpError::Internal(e) => write!(f, "Internal error: {}", e),
+            AcpError::BudgetExceeded {
+                spent,
+                budget,
+                attempted,
+            } => write!(
+                f,
+                "Budget exceeded: {:.2} already spent + {:.2} attempted would exceed the {:.2} cent budget",
+                spent, attempted, budget
+            ),
         }
     }
 }
 
+impl AcpError {
+    /// Whether retrying the same call again has any chance of succeeding.
+    /// `false` for errors that stem from the request itself (a missing
+    /// agent, a version mismatch, a bad signature) rather than a transient
+    /// condition.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            AcpError::NetworkError(_) | AcpError::Timeout(_) | AcpError::Internal(_)
+        )
+    }
+}
+
 impl std::error::Error for AcpError {}
 
 impl From<reqwest::Error> for AcpError {
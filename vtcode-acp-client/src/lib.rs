@@ -10,11 +10,20 @@ pub mod client;
 pub mod discovery;
 pub mod error;
 pub mod messages;
+pub mod signing;
+pub mod transport;
 
-pub use client::{AcpClient, AcpClientBuilder};
-pub use discovery::{AgentInfo, AgentRegistry};
+pub use client::{AcpClient, AcpClientBuilder, AcpFailure, ErrChan, RetryPolicy};
+pub use discovery::{
+    AgentInfo, AgentRegistry, DiscoveryContext, DiscoveryGraph, NegotiatedProtocol, ReaperHandle,
+};
 pub use error::{AcpError, AcpResult};
-pub use messages::{AcpMessage, AcpRequest, AcpResponse};
+pub use messages::{
+    AcpBatchRequest, AcpBatchResponse, AcpHandshake, AcpMessage, AcpRequest, AcpResponse,
+    NegotiatedSession, ResponseFrame, ResponseStatus, ResponseStream, CURRENT_PROTOCOL_VERSION,
+};
+pub use signing::{DEFAULT_SKEW_WINDOW, SigningKey, VerifyKey, verify_incoming_request};
+pub use transport::{CompressionCodec, NegotiatedTransport, ReconnectPolicy};
 
 use agent_client_protocol::AgentSideConnection;
 use std::sync::{Arc, OnceLock};
@@ -1,9 +1,18 @@
 //! ACP message types and serialization
 
+use crate::error::{AcpError, AcpResult};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashSet;
 use uuid::Uuid;
 
+/// Highest ACP protocol version this build understands.
+pub const CURRENT_PROTOCOL_VERSION: u32 = 1;
+
+fn default_protocol_version() -> u32 {
+    CURRENT_PROTOCOL_VERSION
+}
+
 /// Core ACP message envelope
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AcpMessage {
@@ -29,6 +38,13 @@ pub struct AcpMessage {
     /// Optional correlation ID for request/response pairs
     #[serde(skip_serializing_if = "Option::is_none")]
     pub correlation_id: Option<String>,
+
+    /// ACP protocol version this message was produced against.
+    ///
+    /// Defaults to `1` so peers built before the handshake was introduced
+    /// still deserialize cleanly.
+    #[serde(default = "default_protocol_version")]
+    pub protocol_version: u32,
 }
 
 /// Message type enumeration
@@ -39,6 +55,7 @@ pub enum MessageType {
     Response,
     Error,
     Notification,
+    Handshake,
 }
 
 /// Message content payload
@@ -54,10 +71,122 @@ pub enum MessageContent {
     /// Error response
     Error(ErrorPayload),
 
+    /// Protocol version and capability handshake, sent once on connect
+    Handshake(AcpHandshake),
+
+    /// Batch of requests to execute in a single round-trip
+    Batch(AcpBatchRequest),
+
+    /// Results of a batch execution, preserving request order
+    BatchResult(AcpBatchResponse),
+
     /// Generic notification
     Notification(NotificationPayload),
 }
 
+/// A batch of ACP requests to execute together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcpBatchRequest {
+    /// Sub-requests to execute, in order
+    pub operations: Vec<AcpRequest>,
+
+    /// If true, any sub-action failure fails the whole batch and skips the rest
+    /// (unless `continue_on_error` is also set)
+    #[serde(default)]
+    pub atomic: bool,
+
+    /// If true, run every operation even after one fails, reporting per-item status
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// Results of executing an `AcpBatchRequest`, one response per operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcpBatchResponse {
+    /// Per-operation results, in the same order as the request's `operations`
+    pub results: Vec<AcpResponse>,
+}
+
+impl AcpBatchRequest {
+    /// Execute each operation in order via `exec`, honoring `atomic` and
+    /// `continue_on_error`. Returns one `AcpResponse` per operation that was
+    /// actually attempted; operations skipped after an atomic failure are not
+    /// represented in the result.
+    pub async fn execute_with<F, Fut>(&self, mut exec: F) -> AcpBatchResponse
+    where
+        F: FnMut(&AcpRequest) -> Fut,
+        Fut: std::future::Future<Output = AcpResponse>,
+    {
+        let mut results = Vec::with_capacity(self.operations.len());
+
+        for operation in &self.operations {
+            let response = exec(operation).await;
+            let failed = response.status == ResponseStatus::Failed;
+            results.push(response);
+
+            if failed && self.atomic && !self.continue_on_error {
+                break;
+            }
+        }
+
+        AcpBatchResponse { results }
+    }
+
+    /// Whether every completed operation in the response succeeded
+    pub fn all_succeeded(response: &AcpBatchResponse) -> bool {
+        response
+            .results
+            .iter()
+            .all(|r| r.status == ResponseStatus::Success)
+    }
+}
+
+/// Protocol version and capability advertisement exchanged on connect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcpHandshake {
+    /// Integer protocol version this peer speaks
+    pub protocol_version: u32,
+
+    /// Capability tokens this peer supports (e.g. "batch", "streaming")
+    pub capabilities: Vec<String>,
+}
+
+/// Result of negotiating a handshake with a peer
+#[derive(Debug, Clone)]
+pub struct NegotiatedSession {
+    /// The lower of the two peers' protocol versions
+    pub version: u32,
+
+    /// Capabilities both peers advertised
+    pub capabilities: HashSet<String>,
+}
+
+impl NegotiatedSession {
+    /// Negotiate a session from our own handshake and the peer's handshake
+    pub fn negotiate(ours: &AcpHandshake, theirs: &AcpHandshake) -> Self {
+        let ours_caps: HashSet<String> = ours.capabilities.iter().cloned().collect();
+        let theirs_caps: HashSet<String> = theirs.capabilities.iter().cloned().collect();
+        Self {
+            version: ours.protocol_version.min(theirs.protocol_version),
+            capabilities: ours_caps.intersection(&theirs_caps).cloned().collect(),
+        }
+    }
+
+    /// Whether a capability was negotiated with the peer
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.contains(capability)
+    }
+
+    /// Return an error unless the given capability was negotiated
+    pub fn require(&self, capability: &str) -> AcpResult<()> {
+        if self.supports(capability) {
+            Ok(())
+        } else {
+            Err(AcpError::CapabilityNotNegotiated(capability.to_string()))
+        }
+    }
+}
+
 /// ACP request structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AcpRequest {
@@ -158,9 +287,24 @@ impl AcpMessage {
             }),
             timestamp: chrono::Utc::now().to_rfc3339(),
             correlation_id: None,
+            protocol_version: CURRENT_PROTOCOL_VERSION,
         }
     }
 
+    /// Create a new ACP request message, refusing to emit it if `action` relies
+    /// on a capability the peer didn't advertise during the handshake.
+    pub fn request_with_capability(
+        sender: String,
+        recipient: String,
+        action: String,
+        args: Value,
+        required_capability: &str,
+        session: &NegotiatedSession,
+    ) -> AcpResult<Self> {
+        session.require(required_capability)?;
+        Ok(Self::request(sender, recipient, action, args))
+    }
+
     /// Create a new ACP response message
     pub fn response(
         sender: String,
@@ -181,9 +325,24 @@ impl AcpMessage {
             }),
             timestamp: chrono::Utc::now().to_rfc3339(),
             correlation_id: Some(correlation_id),
+            protocol_version: CURRENT_PROTOCOL_VERSION,
         }
     }
 
+    /// Create a response message, refusing to emit it if the response relies
+    /// on a capability the peer didn't advertise during the handshake.
+    pub fn response_with_capability(
+        sender: String,
+        recipient: String,
+        result: Value,
+        correlation_id: String,
+        required_capability: &str,
+        session: &NegotiatedSession,
+    ) -> AcpResult<Self> {
+        session.require(required_capability)?;
+        Ok(Self::response(sender, recipient, result, correlation_id))
+    }
+
     /// Create an error response
     pub fn error_response(
         sender: String,
@@ -204,6 +363,67 @@ impl AcpMessage {
             }),
             timestamp: chrono::Utc::now().to_rfc3339(),
             correlation_id: Some(correlation_id),
+            protocol_version: CURRENT_PROTOCOL_VERSION,
+        }
+    }
+
+    /// Create a new ACP batch request message
+    pub fn batch(
+        sender: String,
+        recipient: String,
+        operations: Vec<AcpRequest>,
+        atomic: bool,
+        continue_on_error: bool,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            message_type: MessageType::Request,
+            sender,
+            recipient,
+            content: MessageContent::Batch(AcpBatchRequest {
+                operations,
+                atomic,
+                continue_on_error,
+            }),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            correlation_id: None,
+            protocol_version: CURRENT_PROTOCOL_VERSION,
+        }
+    }
+
+    /// Create a progress notification carrying a partial result chunk for a
+    /// still-running request, tagged with that request's `correlation_id` so
+    /// the caller can match it to the eventual terminal `AcpResponse`.
+    pub fn progress(sender: String, recipient: String, correlation_id: String, chunk: Value) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            message_type: MessageType::Notification,
+            sender,
+            recipient,
+            content: MessageContent::Notification(NotificationPayload {
+                event: "progress".to_string(),
+                data: chunk,
+            }),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            correlation_id: Some(correlation_id),
+            protocol_version: CURRENT_PROTOCOL_VERSION,
+        }
+    }
+
+    /// Create a handshake notification advertising our protocol version and capabilities
+    pub fn handshake(sender: String, recipient: String, capabilities: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            message_type: MessageType::Handshake,
+            sender,
+            recipient,
+            content: MessageContent::Handshake(AcpHandshake {
+                protocol_version: CURRENT_PROTOCOL_VERSION,
+                capabilities,
+            }),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            correlation_id: None,
+            protocol_version: CURRENT_PROTOCOL_VERSION,
         }
     }
 
@@ -212,9 +432,93 @@ impl AcpMessage {
         Ok(serde_json::to_string(self)?)
     }
 
-    /// Parse from JSON
+    /// Parse from JSON, rejecting messages from a peer speaking a protocol
+    /// major version newer than this build understands.
     pub fn from_json(json: &str) -> anyhow::Result<Self> {
-        Ok(serde_json::from_str(json)?)
+        let message: Self = serde_json::from_str(json)?;
+        if message.protocol_version > CURRENT_PROTOCOL_VERSION {
+            return Err(AcpError::UnsupportedProtocolVersion {
+                ours: CURRENT_PROTOCOL_VERSION,
+                theirs: message.protocol_version,
+            }
+            .into());
+        }
+        Ok(message)
+    }
+}
+
+/// A single frame produced while consuming a streaming ACP response: either
+/// a partial progress chunk or the terminal response.
+#[derive(Debug, Clone)]
+pub enum ResponseFrame {
+    Progress(NotificationPayload),
+    Final(AcpResponse),
+}
+
+/// Consumer-side handle for a streamed action. Peers that negotiated the
+/// `"streaming"` capability may emit zero or more `progress` notifications
+/// before the terminal `AcpResponse`; `ResponseStream` filters incoming
+/// messages down to the ones matching `correlation_id` and exposes them as a
+/// sequence of frames, resolving to the final response.
+pub struct ResponseStream {
+    correlation_id: String,
+    receiver: tokio::sync::mpsc::Receiver<AcpMessage>,
+}
+
+impl ResponseStream {
+    /// Build a stream over a channel fed by the transport layer as messages
+    /// for `correlation_id` arrive.
+    pub fn new(correlation_id: String, receiver: tokio::sync::mpsc::Receiver<AcpMessage>) -> Self {
+        Self {
+            correlation_id,
+            receiver,
+        }
+    }
+
+    /// Await the next progress frame or the terminal response. Returns
+    /// `Ok(None)` once the underlying channel closes without ever delivering
+    /// a terminal response.
+    pub async fn next_frame(&mut self) -> AcpResult<Option<ResponseFrame>> {
+        while let Some(message) = self.receiver.recv().await {
+            if message.correlation_id.as_deref() != Some(self.correlation_id.as_str()) {
+                continue;
+            }
+
+            match message.content {
+                MessageContent::Notification(payload) if payload.event == "progress" => {
+                    return Ok(Some(ResponseFrame::Progress(payload)));
+                }
+                MessageContent::Response(response) => {
+                    return Ok(Some(ResponseFrame::Final(response)));
+                }
+                MessageContent::Error(error) => {
+                    return Err(AcpError::RemoteError {
+                        agent_id: message.sender,
+                        message: error.message,
+                        code: None,
+                    });
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Drain all progress frames, discarding them, and return the terminal
+    /// response. Used by callers that only care about the final result.
+    pub async fn resolve(&mut self) -> AcpResult<AcpResponse> {
+        loop {
+            match self.next_frame().await? {
+                Some(ResponseFrame::Progress(_)) => continue,
+                Some(ResponseFrame::Final(response)) => return Ok(response),
+                None => {
+                    return Err(AcpError::Internal(
+                        "response stream closed before a terminal response arrived".to_string(),
+                    ));
+                }
+            }
+        }
     }
 }
 
@@ -252,4 +556,224 @@ mod tests {
         assert_eq!(msg.id, restored.id);
         assert_eq!(msg.sender, restored.sender);
     }
+
+    #[test]
+    fn test_handshake_negotiation_intersects_capabilities() {
+        let ours = AcpHandshake {
+            protocol_version: 2,
+            capabilities: vec!["batch".to_string(), "streaming".to_string()],
+        };
+        let theirs = AcpHandshake {
+            protocol_version: 1,
+            capabilities: vec!["streaming".to_string(), "binary-args".to_string()],
+        };
+
+        let session = NegotiatedSession::negotiate(&ours, &theirs);
+
+        assert_eq!(session.version, 1);
+        assert!(session.supports("streaming"));
+        assert!(!session.supports("batch"));
+        assert!(!session.supports("binary-args"));
+    }
+
+    #[test]
+    fn test_request_with_capability_rejects_unnegotiated() {
+        let session = NegotiatedSession {
+            version: 1,
+            capabilities: std::collections::HashSet::new(),
+        };
+
+        let result = AcpMessage::request_with_capability(
+            "agent-1".to_string(),
+            "agent-2".to_string(),
+            "run_batch".to_string(),
+            json!({}),
+            "batch",
+            &session,
+        );
+
+        assert!(matches!(
+            result,
+            Err(AcpError::CapabilityNotNegotiated(cap)) if cap == "batch"
+        ));
+    }
+
+    #[test]
+    fn test_old_peer_message_without_protocol_version_defaults_to_one() {
+        let json = r#"{
+            "id": "abc",
+            "type": "notification",
+            "sender": "agent-1",
+            "recipient": "agent-2",
+            "content": {"event": "ping", "data": {}},
+            "timestamp": "2024-01-01T00:00:00Z"
+        }"#;
+
+        let msg = AcpMessage::from_json(json).unwrap();
+        assert_eq!(msg.protocol_version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_atomic_stops_after_first_failure() {
+        let batch = AcpBatchRequest {
+            operations: vec![
+                AcpRequest {
+                    action: "ok".to_string(),
+                    args: json!({}),
+                    timeout_secs: None,
+                    sync: true,
+                },
+                AcpRequest {
+                    action: "boom".to_string(),
+                    args: json!({}),
+                    timeout_secs: None,
+                    sync: true,
+                },
+                AcpRequest {
+                    action: "never_runs".to_string(),
+                    args: json!({}),
+                    timeout_secs: None,
+                    sync: true,
+                },
+            ],
+            atomic: true,
+            continue_on_error: false,
+        };
+
+        let response = batch
+            .execute_with(|req| {
+                let failed = req.action == "boom";
+                async move {
+                    AcpResponse {
+                        status: if failed {
+                            ResponseStatus::Failed
+                        } else {
+                            ResponseStatus::Success
+                        },
+                        result: None,
+                        error: None,
+                        execution_time_ms: 0,
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(response.results.len(), 2);
+        assert!(!AcpBatchRequest::all_succeeded(&response));
+    }
+
+    #[tokio::test]
+    async fn test_batch_continue_on_error_runs_everything() {
+        let batch = AcpBatchRequest {
+            operations: vec![
+                AcpRequest {
+                    action: "boom".to_string(),
+                    args: json!({}),
+                    timeout_secs: None,
+                    sync: true,
+                },
+                AcpRequest {
+                    action: "ok".to_string(),
+                    args: json!({}),
+                    timeout_secs: None,
+                    sync: true,
+                },
+            ],
+            atomic: true,
+            continue_on_error: true,
+        };
+
+        let response = batch
+            .execute_with(|req| {
+                let failed = req.action == "boom";
+                async move {
+                    AcpResponse {
+                        status: if failed {
+                            ResponseStatus::Failed
+                        } else {
+                            ResponseStatus::Success
+                        },
+                        result: None,
+                        error: None,
+                        execution_time_ms: 0,
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(response.results.len(), 2);
+        assert!(!AcpBatchRequest::all_succeeded(&response));
+    }
+
+    #[tokio::test]
+    async fn test_response_stream_yields_progress_then_resolves() {
+        let (tx, rx) = tokio::sync::mpsc::channel(8);
+        let mut stream = ResponseStream::new("corr-1".to_string(), rx);
+
+        tx.send(AcpMessage::progress(
+            "agent-1".to_string(),
+            "agent-2".to_string(),
+            "corr-1".to_string(),
+            json!({"line": "building..."}),
+        ))
+        .await
+        .unwrap();
+        tx.send(AcpMessage::response(
+            "agent-1".to_string(),
+            "agent-2".to_string(),
+            json!({"ok": true}),
+            "corr-1".to_string(),
+        ))
+        .await
+        .unwrap();
+        drop(tx);
+
+        let first = stream.next_frame().await.unwrap().unwrap();
+        assert!(matches!(first, ResponseFrame::Progress(_)));
+
+        let resolved = stream.resolve().await.unwrap();
+        assert_eq!(resolved.status, ResponseStatus::Success);
+    }
+
+    #[tokio::test]
+    async fn test_response_stream_ignores_other_correlations() {
+        let (tx, rx) = tokio::sync::mpsc::channel(8);
+        let mut stream = ResponseStream::new("corr-1".to_string(), rx);
+
+        tx.send(AcpMessage::progress(
+            "agent-1".to_string(),
+            "agent-2".to_string(),
+            "other-correlation".to_string(),
+            json!({}),
+        ))
+        .await
+        .unwrap();
+        tx.send(AcpMessage::response(
+            "agent-1".to_string(),
+            "agent-2".to_string(),
+            json!({}),
+            "corr-1".to_string(),
+        ))
+        .await
+        .unwrap();
+        drop(tx);
+
+        let resolved = stream.resolve().await.unwrap();
+        assert_eq!(resolved.status, ResponseStatus::Success);
+    }
+
+    #[test]
+    fn test_from_json_rejects_future_major_version() {
+        let json = r#"{
+            "id": "abc",
+            "type": "notification",
+            "sender": "agent-1",
+            "recipient": "agent-2",
+            "content": {"event": "ping", "data": {}},
+            "timestamp": "2024-01-01T00:00:00Z",
+            "protocol_version": 999
+        }"#;
+
+        assert!(AcpMessage::from_json(json).is_err());
+    }
 }
@@ -0,0 +1,343 @@
+//! HTTP message signing for ACP requests
+//!
+//! Modeled on the signing scheme used by federated agent protocols: a
+//! `Digest` header carries a SHA-256 hash of the body, and a `Signature`
+//! header carries a key id plus a signature over a canonical string built
+//! from `(request-target)`, `host`, `date`, and `digest`.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ed25519_dalek::{Signature, Signer, SigningKey as Ed25519SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest as _, Sha256};
+use std::time::Duration;
+
+use crate::discovery::AgentRegistry;
+use crate::error::{AcpError, AcpResult};
+
+/// The headers covered by the signing string, in order.
+const SIGNED_HEADERS: &str = "(request-target) host date digest";
+
+/// Default replay-protection window: a request whose `Date` header is
+/// further than this from "now" (either direction, to tolerate clock skew
+/// between agents) is rejected even if its signature is otherwise valid.
+pub const DEFAULT_SKEW_WINDOW: Duration = Duration::from_secs(300);
+
+/// A local agent's signing key, used to authenticate outgoing requests.
+pub enum SigningKey {
+    Ed25519(Ed25519SigningKey),
+}
+
+impl SigningKey {
+    /// Load an Ed25519 signing key from 32 raw seed bytes.
+    pub fn from_ed25519_bytes(seed: &[u8; 32]) -> Self {
+        SigningKey::Ed25519(Ed25519SigningKey::from_bytes(seed))
+    }
+
+    /// The key id advertised in the `Signature` header, derived from the
+    /// public key so verifiers can look it up without extra metadata.
+    pub fn key_id(&self) -> String {
+        match self {
+            SigningKey::Ed25519(key) => BASE64.encode(key.verifying_key().to_bytes()),
+        }
+    }
+
+    fn sign(&self, signing_string: &str) -> String {
+        match self {
+            SigningKey::Ed25519(key) => {
+                let signature = key.sign(signing_string.as_bytes());
+                BASE64.encode(signature.to_bytes())
+            }
+        }
+    }
+}
+
+/// A remote agent's public key, used to verify requests it claims to have sent.
+pub enum VerifyKey {
+    Ed25519(VerifyingKey),
+}
+
+impl VerifyKey {
+    /// Parse a public key from the base64 encoding advertised in `AgentInfo::public_key`.
+    pub fn from_base64(encoded: &str) -> AcpResult<Self> {
+        let bytes = BASE64
+            .decode(encoded)
+            .map_err(|err| AcpError::SignatureError(format!("invalid public key encoding: {err}")))?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| AcpError::SignatureError("public key must be 32 bytes".to_string()))?;
+        let key = VerifyingKey::from_bytes(&bytes)
+            .map_err(|err| AcpError::SignatureError(format!("invalid public key: {err}")))?;
+        Ok(VerifyKey::Ed25519(key))
+    }
+}
+
+/// The headers a signed request must carry, ready to attach via `reqwest::RequestBuilder::header`.
+pub struct SignedHeaders {
+    pub digest: String,
+    pub date: String,
+    pub signature: String,
+}
+
+/// Compute the `Digest` header value for a request body.
+pub fn digest_header(body: &[u8]) -> String {
+    let hash = Sha256::digest(body);
+    format!("SHA-256={}", BASE64.encode(hash))
+}
+
+fn signing_string(method_and_path: &str, host: &str, date: &str, digest: &str) -> String {
+    format!(
+        "(request-target): {method_and_path}\nhost: {host}\ndate: {date}\ndigest: {digest}"
+    )
+}
+
+/// Sign an outgoing request, producing the headers to attach to it.
+pub fn sign_request(
+    key: &SigningKey,
+    method_and_path: &str,
+    host: &str,
+    date: &str,
+    body: &[u8],
+) -> SignedHeaders {
+    let digest = digest_header(body);
+    let signing_string = signing_string(method_and_path, host, &date.to_string(), &digest);
+    let signature = key.sign(&signing_string);
+
+    SignedHeaders {
+        digest,
+        date: date.to_string(),
+        signature: format!(
+            "keyId=\"{}\",algorithm=\"ed25519\",headers=\"{}\",signature=\"{}\"",
+            key.key_id(),
+            SIGNED_HEADERS,
+            signature
+        ),
+    }
+}
+
+/// Verify a received request's `Signature` header against the claimed public key.
+pub fn verify_request(
+    key: &VerifyKey,
+    method_and_path: &str,
+    host: &str,
+    date: &str,
+    digest: &str,
+    body: &[u8],
+    signature_b64: &str,
+) -> AcpResult<()> {
+    let expected_digest = digest_header(body);
+    if expected_digest != digest {
+        return Err(AcpError::SignatureError(
+            "digest does not match request body".to_string(),
+        ));
+    }
+
+    let signing_string = signing_string(method_and_path, host, date, digest);
+    let signature_bytes = BASE64
+        .decode(signature_b64)
+        .map_err(|err| AcpError::SignatureError(format!("invalid signature encoding: {err}")))?;
+    let signature_bytes: [u8; 64] = signature_bytes
+        .try_into()
+        .map_err(|_| AcpError::SignatureError("signature must be 64 bytes".to_string()))?;
+    let signature = Signature::from_bytes(&signature_bytes);
+
+    match key {
+        VerifyKey::Ed25519(verifying_key) => verifying_key
+            .verify(signing_string.as_bytes(), &signature)
+            .map_err(|err| AcpError::SignatureError(format!("signature verification failed: {err}"))),
+    }
+}
+
+/// Verify an incoming request claiming to be from `sender_agent_id`: looks
+/// up its public key in `registry`, rejects the request if `date` falls
+/// outside `skew_window` of now (replay protection), then recomputes the
+/// digest and checks the signature. Always surfaces
+/// `AcpError::SignatureInvalid` — never the lower-level `SignatureError`
+/// that `verify_request`/key-parsing raise internally — so callers have one
+/// error shape to branch on for "reject this request".
+pub async fn verify_incoming_request(
+    registry: &AgentRegistry,
+    sender_agent_id: &str,
+    method_and_path: &str,
+    host: &str,
+    date: &str,
+    digest: &str,
+    body: &[u8],
+    signature_b64: &str,
+    skew_window: Duration,
+) -> AcpResult<()> {
+    check_date_skew(date, skew_window)?;
+
+    let key = registry
+        .verify_key(sender_agent_id)
+        .await
+        .map_err(|err| AcpError::SignatureInvalid(format!("unknown sender: {err}")))?
+        .ok_or_else(|| {
+            AcpError::SignatureInvalid(format!(
+                "agent `{sender_agent_id}` has no registered public key"
+            ))
+        })?;
+
+    verify_request(&key, method_and_path, host, date, digest, body, signature_b64)
+        .map_err(|err| AcpError::SignatureInvalid(err.to_string()))
+}
+
+/// Reject a request whose RFC 2822 `Date` header is further than
+/// `skew_window` from now, in either direction.
+fn check_date_skew(date: &str, skew_window: Duration) -> AcpResult<()> {
+    let sent_at = chrono::DateTime::parse_from_rfc2822(date)
+        .map_err(|err| AcpError::SignatureInvalid(format!("invalid Date header: {err}")))?;
+    let age = chrono::Utc::now()
+        .signed_duration_since(sent_at)
+        .abs()
+        .to_std()
+        .unwrap_or(Duration::MAX);
+    if age > skew_window {
+        return Err(AcpError::SignatureInvalid(format!(
+            "Date header {date} is outside the {skew_window:?} replay window"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify_roundtrip() {
+        let seed = [7u8; 32];
+        let signing_key = SigningKey::from_ed25519_bytes(&seed);
+        let verify_key = VerifyKey::from_base64(&signing_key.key_id()).unwrap();
+
+        let body = b"{\"hello\":\"world\"}";
+        let headers = sign_request(&signing_key, "post /messages", "agent.local", "2024-01-01T00:00:00Z", body);
+
+        let signature = headers
+            .signature
+            .split("signature=\"")
+            .nth(1)
+            .unwrap()
+            .trim_end_matches('"');
+
+        verify_request(
+            &verify_key,
+            "post /messages",
+            "agent.local",
+            &headers.date,
+            &headers.digest,
+            body,
+            signature,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_body() {
+        let seed = [3u8; 32];
+        let signing_key = SigningKey::from_ed25519_bytes(&seed);
+        let verify_key = VerifyKey::from_base64(&signing_key.key_id()).unwrap();
+
+        let body = b"original";
+        let headers = sign_request(&signing_key, "post /messages", "agent.local", "2024-01-01T00:00:00Z", body);
+        let signature = headers
+            .signature
+            .split("signature=\"")
+            .nth(1)
+            .unwrap()
+            .trim_end_matches('"');
+
+        let result = verify_request(
+            &verify_key,
+            "post /messages",
+            "agent.local",
+            &headers.date,
+            &headers.digest,
+            b"tampered",
+            signature,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_date_skew_rejects_stale_date() {
+        let stale = (chrono::Utc::now() - chrono::Duration::seconds(600)).to_rfc2822();
+        let result = check_date_skew(&stale, Duration::from_secs(300));
+        assert!(matches!(result, Err(AcpError::SignatureInvalid(_))));
+    }
+
+    #[test]
+    fn test_date_skew_accepts_fresh_date() {
+        let fresh = chrono::Utc::now().to_rfc2822();
+        check_date_skew(&fresh, Duration::from_secs(300)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_incoming_request_roundtrip() {
+        let seed = [9u8; 32];
+        let signing_key = SigningKey::from_ed25519_bytes(&seed);
+        let key_id = signing_key.key_id();
+
+        let registry = AgentRegistry::new();
+        registry
+            .register(crate::discovery::AgentInfo {
+                id: "sender".to_string(),
+                name: "Sender".to_string(),
+                base_url: "http://sender.local".to_string(),
+                description: None,
+                capabilities: Vec::new(),
+                metadata: std::collections::HashMap::new(),
+                online: true,
+                last_seen: None,
+                public_key: Some(key_id),
+                known_peers: Vec::new(),
+                additional_endpoints: Vec::new(),
+                heartbeat_interval_secs: 30,
+                protocol_version: semver::Version::new(1, 0, 0),
+            })
+            .await
+            .unwrap();
+
+        let body = b"{\"hello\":\"world\"}";
+        let date = chrono::Utc::now().to_rfc2822();
+        let headers = sign_request(&signing_key, "post /messages", "agent.local", &date, body);
+        let signature = headers
+            .signature
+            .split("signature=\"")
+            .nth(1)
+            .unwrap()
+            .trim_end_matches('"');
+
+        verify_incoming_request(
+            &registry,
+            "sender",
+            "post /messages",
+            "agent.local",
+            &headers.date,
+            &headers.digest,
+            body,
+            signature,
+            DEFAULT_SKEW_WINDOW,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_incoming_request_rejects_unknown_sender() {
+        let registry = AgentRegistry::new();
+        let result = verify_incoming_request(
+            &registry,
+            "ghost",
+            "post /messages",
+            "agent.local",
+            &chrono::Utc::now().to_rfc2822(),
+            "SHA-256=bogus",
+            b"body",
+            "bogus",
+            DEFAULT_SKEW_WINDOW,
+        )
+        .await;
+        assert!(matches!(result, Err(AcpError::SignatureInvalid(_))));
+    }
+}
@@ -0,0 +1,156 @@
+//! Transport-level handshake: compression codec negotiation and
+//! reconnection policy for the HTTP transport underlying `AcpClient`.
+
+use std::io::Write;
+use std::time::Duration;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AcpError, AcpResult};
+
+/// Compression codecs a transport handshake may negotiate for large
+/// `args`/response payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionCodec {
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl CompressionCodec {
+    /// The `Content-Encoding` header value for this codec, or `None` for
+    /// `Identity`.
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            CompressionCodec::Identity => None,
+            CompressionCodec::Gzip => Some("gzip"),
+            CompressionCodec::Zstd => Some("zstd"),
+        }
+    }
+
+    /// Compress `body` with this codec, returning it unchanged for `Identity`.
+    pub fn compress(self, body: &[u8]) -> AcpResult<Vec<u8>> {
+        match self {
+            CompressionCodec::Identity => Ok(body.to_vec()),
+            CompressionCodec::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(body)
+                    .map_err(|err| AcpError::Internal(format!("gzip compression failed: {err}")))?;
+                encoder
+                    .finish()
+                    .map_err(|err| AcpError::Internal(format!("gzip compression failed: {err}")))
+            }
+            CompressionCodec::Zstd => zstd::stream::encode_all(body, 0)
+                .map_err(|err| AcpError::Internal(format!("zstd compression failed: {err}"))),
+        }
+    }
+}
+
+/// What our side offers during a transport handshake.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransportHandshakeRequest {
+    /// Codecs we can encode/decode, most preferred first
+    pub supported_codecs: Vec<CompressionCodec>,
+
+    /// Seconds between keep-alive pings this client intends to send
+    pub keep_alive_secs: u64,
+}
+
+/// What the remote side picked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransportHandshakeResponse {
+    /// The codec the remote agent wants us to use for request bodies
+    pub codec: CompressionCodec,
+
+    /// Seconds between keep-alive pings the remote agent expects
+    pub keep_alive_secs: u64,
+}
+
+/// Negotiated transport state cached per agent after a successful handshake.
+#[derive(Debug, Clone, Copy)]
+pub struct NegotiatedTransport {
+    pub codec: CompressionCodec,
+    pub keep_alive: Duration,
+}
+
+/// Backoff policy for re-running the handshake after a previously
+/// unreachable agent becomes reachable again, so a flapping peer doesn't
+/// trigger a tight reconnect loop.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Backoff to wait before the `attempt`-th reconnect (1-indexed),
+    /// doubling each time up to `max_backoff`.
+    pub fn backoff_for_attempt(&self, attempt: u32) -> Duration {
+        let scale = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(scale)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// The codecs this build can encode/decode, most preferred first.
+pub fn supported_codecs() -> Vec<CompressionCodec> {
+    vec![
+        CompressionCodec::Zstd,
+        CompressionCodec::Gzip,
+        CompressionCodec::Identity,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gzip_compress_changes_bytes() {
+        let body = b"hello hello hello hello hello";
+        let compressed = CompressionCodec::Gzip.compress(body).unwrap();
+        assert_ne!(compressed, body);
+    }
+
+    #[test]
+    fn test_zstd_compress_changes_bytes() {
+        let body = b"hello hello hello hello hello";
+        let compressed = CompressionCodec::Zstd.compress(body).unwrap();
+        assert_ne!(compressed, body);
+    }
+
+    #[test]
+    fn test_identity_is_passthrough() {
+        let body = b"unchanged";
+        let compressed = CompressionCodec::Identity.compress(body).unwrap();
+        assert_eq!(compressed, body);
+    }
+
+    #[test]
+    fn test_reconnect_backoff_doubles_and_caps() {
+        let policy = ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+
+        assert_eq!(policy.backoff_for_attempt(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for_attempt(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for_attempt(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff_for_attempt(4), Duration::from_millis(500));
+    }
+}
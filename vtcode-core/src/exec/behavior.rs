@@ -0,0 +1,501 @@
+//! Agent behavior analysis: tool/skill usage tracking, failure-pattern
+//! detection, and tool recommendations for [`crate::exec::CodeExecutor`]
+//! sessions.
+//!
+//! Recorded failures are also forwarded to a background reporter task over
+//! an `mpsc` queue so an operator-facing sink (an append-only log file, a
+//! remote collector, or both) sees them without blocking the caller, and the
+//! accumulated stats are persisted to disk on a debounced schedule so
+//! `identify_risky_tools`/`recommend_tools` survive a process restart.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+/// Maximum number of attempts to deliver a failure event to a sink before
+/// dropping it.
+const MAX_REPORT_ATTEMPTS: u32 = 3;
+
+/// Base delay for exponential backoff between retried sink deliveries;
+/// doubles on each attempt.
+const REPORT_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
+/// Minimum interval between persisted snapshots of accumulated stats, so a
+/// burst of `record_*` calls doesn't turn into a write per call.
+const PERSIST_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// Per-tool invocation counts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub usage_frequency: HashMap<String, u32>,
+}
+
+/// Skill reuse counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillStats {
+    pub reused_skills: u32,
+    pub reuse_frequency: HashMap<String, u32>,
+}
+
+/// Observed failures per tool, plus the subset whose failure count has
+/// reached the "high failure" threshold. [`AgentBehaviorAnalyzer::identify_risky_tools`]
+/// works from `failures_by_tool` instead, as a failure rate per use.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FailurePatterns {
+    pub failures_by_tool: HashMap<String, Vec<String>>,
+    pub high_failure_tools: Vec<String>,
+}
+
+/// A single recorded tool failure, forwarded to the configured [`FailureSink`]s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolFailureEvent {
+    pub tool: String,
+    pub reason: String,
+    pub timestamp_ms: u64,
+}
+
+/// Destination for recorded tool failures, e.g. an append-only log file or a
+/// remote collector endpoint. Implementations should treat errors as
+/// transient; the reporter retries a bounded number of times before dropping
+/// an event.
+#[async_trait]
+pub trait FailureSink: Send + Sync {
+    async fn report(&self, event: &ToolFailureEvent) -> Result<()>;
+}
+
+/// Sink that appends each failure as one JSON line to a local log file,
+/// creating the file (and its parent directory) if it doesn't exist yet.
+pub struct FileFailureSink {
+    log_path: PathBuf,
+}
+
+impl FileFailureSink {
+    pub fn new(log_path: PathBuf) -> Self {
+        Self { log_path }
+    }
+}
+
+#[async_trait]
+impl FailureSink for FileFailureSink {
+    async fn report(&self, event: &ToolFailureEvent) -> Result<()> {
+        if let Some(parent) = self.log_path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .context("failed to create failure log directory")?;
+        }
+
+        let line = serde_json::to_string(event).context("failed to serialize failure event")?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .await
+            .context("failed to open failure log")?;
+
+        use tokio::io::AsyncWriteExt;
+        file.write_all(line.as_bytes()).await?;
+        file.write_all(b"\n").await?;
+        Ok(())
+    }
+}
+
+/// Sink that forwards each failure as a JSON POST to a remote collector
+/// endpoint.
+pub struct RemoteFailureSink {
+    endpoint: String,
+    http_client: reqwest::Client,
+}
+
+impl RemoteFailureSink {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            endpoint,
+            http_client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl FailureSink for RemoteFailureSink {
+    async fn report(&self, event: &ToolFailureEvent) -> Result<()> {
+        let response = self
+            .http_client
+            .post(&self.endpoint)
+            .json(event)
+            .send()
+            .await
+            .context("failed to send failure event to remote collector")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "remote collector returned {} for failure event",
+                response.status()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A snapshot of the persisted analyzer state, written to
+/// `<skills_dir>/behavior_stats.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PersistedState {
+    tool_stats: ToolStats,
+    skill_stats: SkillStats,
+    failure_patterns: FailurePatterns,
+}
+
+/// Background handle that drains recorded failures and forwards them to the
+/// configured sinks, retrying transient errors with exponential backoff
+/// before dropping an event.
+struct FailureReporter {
+    tx: mpsc::UnboundedSender<ToolFailureEvent>,
+}
+
+impl FailureReporter {
+    fn spawn(sinks: Vec<Arc<dyn FailureSink>>) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<ToolFailureEvent>();
+
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                for sink in &sinks {
+                    let mut attempt = 0u32;
+                    let mut backoff = REPORT_RETRY_BASE_DELAY;
+
+                    loop {
+                        attempt += 1;
+                        match sink.report(&event).await {
+                            Ok(()) => break,
+                            Err(err) if attempt < MAX_REPORT_ATTEMPTS => {
+                                warn!(
+                                    tool = %event.tool,
+                                    attempt,
+                                    error = %err,
+                                    "failure report attempt failed; retrying"
+                                );
+                                tokio::time::sleep(backoff).await;
+                                backoff *= 2;
+                            }
+                            Err(err) => {
+                                warn!(
+                                    tool = %event.tool,
+                                    attempt,
+                                    error = %err,
+                                    "giving up on failure report after max attempts"
+                                );
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        Self { tx }
+    }
+
+    fn send(&self, event: ToolFailureEvent) {
+        let _ = self.tx.send(event);
+    }
+}
+
+/// Tracks tool usage, skill reuse, and failure patterns for an agent session,
+/// producing risk-aware tool recommendations.
+///
+/// Call [`Self::with_persistence`] to seed the next session's thresholds
+/// from a prior run, and [`Self::with_reporter`] to forward failures to a
+/// durable sink as they're recorded.
+pub struct AgentBehaviorAnalyzer {
+    tool_stats: ToolStats,
+    skill_stats: SkillStats,
+    failure_patterns: FailurePatterns,
+    reporter: Option<FailureReporter>,
+    persist_path: Option<PathBuf>,
+    last_persisted: Option<Instant>,
+}
+
+impl AgentBehaviorAnalyzer {
+    /// Create an analyzer with empty in-memory stats and no reporting or
+    /// persistence configured.
+    pub fn new() -> Self {
+        Self {
+            tool_stats: ToolStats::default(),
+            skill_stats: SkillStats::default(),
+            failure_patterns: FailurePatterns::default(),
+            reporter: None,
+            persist_path: None,
+            last_persisted: None,
+        }
+    }
+
+    /// Load prior stats from `<skills_dir>/behavior_stats.json` if present,
+    /// and persist future updates back to the same path on a debounced
+    /// schedule.
+    pub async fn with_persistence(mut self, skills_dir: &Path) -> Result<Self> {
+        let path = skills_dir.join("behavior_stats.json");
+
+        if let Ok(bytes) = fs::read(&path).await {
+            match serde_json::from_slice::<PersistedState>(&bytes) {
+                Ok(state) => {
+                    debug!(path = ?path, "loaded persisted behavior stats");
+                    self.tool_stats = state.tool_stats;
+                    self.skill_stats = state.skill_stats;
+                    self.failure_patterns = state.failure_patterns;
+                }
+                Err(err) => {
+                    warn!(path = ?path, error = %err, "ignoring unreadable behavior stats file");
+                }
+            }
+        }
+
+        self.persist_path = Some(path);
+        Ok(self)
+    }
+
+    /// Forward every recorded tool failure to `sinks` via a background
+    /// reporter task, retrying transient errors before dropping an event.
+    pub fn with_reporter(mut self, sinks: Vec<Arc<dyn FailureSink>>) -> Self {
+        self.reporter = Some(FailureReporter::spawn(sinks));
+        self
+    }
+
+    pub fn tool_stats(&self) -> &ToolStats {
+        &self.tool_stats
+    }
+
+    pub fn skill_stats(&self) -> &SkillStats {
+        &self.skill_stats
+    }
+
+    pub fn failure_patterns(&self) -> &FailurePatterns {
+        &self.failure_patterns
+    }
+
+    /// Record a use of `tool_name`.
+    pub fn record_tool_usage(&mut self, tool_name: &str) {
+        *self
+            .tool_stats
+            .usage_frequency
+            .entry(tool_name.to_string())
+            .or_insert(0) += 1;
+        self.persist_if_due();
+    }
+
+    /// Record that `skill_name` was reused rather than regenerated.
+    pub fn record_skill_reuse(&mut self, skill_name: &str) {
+        self.skill_stats.reused_skills += 1;
+        *self
+            .skill_stats
+            .reuse_frequency
+            .entry(skill_name.to_string())
+            .or_insert(0) += 1;
+        self.persist_if_due();
+    }
+
+    /// Record a failure of `tool_name` with a human-readable `reason`. If a
+    /// reporter is configured, the event is also queued for delivery to the
+    /// durable sink(s); if persistence is configured, accumulated stats are
+    /// flushed to disk once the debounce interval has elapsed.
+    pub fn record_tool_failure(&mut self, tool_name: &str, reason: &str) {
+        let failures = self
+            .failure_patterns
+            .failures_by_tool
+            .entry(tool_name.to_string())
+            .or_default();
+        failures.push(reason.to_string());
+
+        if failures.len() >= high_failure_threshold() {
+            if !self
+                .failure_patterns
+                .high_failure_tools
+                .iter()
+                .any(|t| t == tool_name)
+            {
+                self.failure_patterns
+                    .high_failure_tools
+                    .push(tool_name.to_string());
+            }
+        }
+
+        if let Some(reporter) = &self.reporter {
+            reporter.send(ToolFailureEvent {
+                tool: tool_name.to_string(),
+                reason: reason.to_string(),
+                timestamp_ms: elapsed_ms_since_process_start(),
+            });
+        }
+
+        self.persist_if_due();
+    }
+
+    /// Recommend up to `limit` known tools whose name contains `query`,
+    /// most-used first.
+    pub fn recommend_tools(&self, query: &str, limit: usize) -> Vec<String> {
+        let mut matches: Vec<(&String, &u32)> = self
+            .tool_stats
+            .usage_frequency
+            .iter()
+            .filter(|(name, _)| name.contains(query))
+            .collect();
+        matches.sort_by(|a, b| b.1.cmp(a.1));
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Tools whose observed failure rate (failures / usage) meets or
+    /// exceeds `threshold`.
+    pub fn identify_risky_tools(&self, threshold: f64) -> Vec<String> {
+        self.failure_patterns
+            .failures_by_tool
+            .iter()
+            .filter(|(tool, failures)| {
+                let usage = self
+                    .tool_stats
+                    .usage_frequency
+                    .get(tool.as_str())
+                    .copied()
+                    .unwrap_or(0)
+                    .max(1) as f64;
+                (failures.len() as f64 / usage) >= threshold
+            })
+            .map(|(tool, _)| tool.clone())
+            .collect()
+    }
+
+    /// Persist the current stats to `persist_path` if configured and the
+    /// debounce interval has elapsed since the last write.
+    fn persist_if_due(&mut self) {
+        let Some(path) = self.persist_path.clone() else {
+            return;
+        };
+
+        let due = match self.last_persisted {
+            Some(last) => last.elapsed() >= PERSIST_DEBOUNCE,
+            None => true,
+        };
+        if !due {
+            return;
+        }
+        self.last_persisted = Some(Instant::now());
+
+        let state = PersistedState {
+            tool_stats: self.tool_stats.clone(),
+            skill_stats: self.skill_stats.clone(),
+            failure_patterns: self.failure_patterns.clone(),
+        };
+
+        tokio::spawn(async move {
+            if let Err(err) = persist_state(&path, &state).await {
+                warn!(path = ?path, error = %err, "failed to persist behavior stats");
+            }
+        });
+    }
+}
+
+impl Default for AgentBehaviorAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn persist_state(path: &Path, state: &PersistedState) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .context("failed to create skills directory")?;
+    }
+    let bytes = serde_json::to_vec_pretty(state).context("failed to serialize behavior stats")?;
+    fs::write(path, bytes)
+        .await
+        .context("failed to write behavior stats")?;
+    debug!(path = ?path, "persisted behavior stats");
+    Ok(())
+}
+
+/// Minimum recorded failures for a tool before it's flagged as a high-failure tool.
+fn high_failure_threshold() -> usize {
+    2
+}
+
+fn elapsed_ms_since_process_start() -> u64 {
+    static START: std::sync::OnceLock<Instant> = std::sync::OnceLock::new();
+    let start = START.get_or_init(Instant::now);
+    start.elapsed().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_usage_and_reuse() {
+        let mut analyzer = AgentBehaviorAnalyzer::new();
+        analyzer.record_tool_usage("list_files");
+        analyzer.record_tool_usage("list_files");
+        analyzer.record_tool_usage("read_file");
+        analyzer.record_skill_reuse("filter_skill");
+        analyzer.record_skill_reuse("filter_skill");
+
+        assert_eq!(
+            analyzer.tool_stats().usage_frequency.get("list_files"),
+            Some(&2)
+        );
+        assert_eq!(analyzer.skill_stats().reused_skills, 2);
+    }
+
+    #[test]
+    fn flags_high_failure_tools_and_recommends() {
+        let mut analyzer = AgentBehaviorAnalyzer::new();
+        analyzer.record_tool_usage("list_files");
+        analyzer.record_tool_failure("grep_tool", "timeout");
+        analyzer.record_tool_failure("grep_tool", "pattern_error");
+
+        assert!(!analyzer.failure_patterns().high_failure_tools.is_empty());
+        assert!(analyzer
+            .recommend_tools("list", 1)
+            .contains(&"list_files".to_string()));
+        assert!(!analyzer.identify_risky_tools(0.3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn persists_and_reloads_stats() {
+        let dir = std::env::temp_dir().join(format!(
+            "vtcode_behavior_test_{}",
+            std::process::id()
+        ));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+
+        let mut analyzer = AgentBehaviorAnalyzer::new()
+            .with_persistence(&dir)
+            .await
+            .unwrap();
+        analyzer.record_tool_usage("list_files");
+        // Force an immediate flush regardless of debounce for the test.
+        analyzer.last_persisted = None;
+        analyzer.persist_if_due();
+        // Give the spawned persistence task a chance to run.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let reloaded = AgentBehaviorAnalyzer::new()
+            .with_persistence(&dir)
+            .await
+            .unwrap();
+        assert_eq!(
+            reloaded.tool_stats().usage_frequency.get("list_files"),
+            Some(&1)
+        );
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
@@ -0,0 +1,188 @@
+//! Host↔sandbox bridge that lets generated SDK code call MCP tools.
+//!
+//! [`CodeExecutor`](crate::exec::CodeExecutor) spawns the sandboxed
+//! interpreter with a `VTCODE_MCP_SOCKET` environment variable pointing at a
+//! Unix domain socket. The generated `_call_tool`/`callTool` wrappers
+//! connect to it once and write one newline-delimited JSON request per tool
+//! call:
+//!
+//! ```json
+//! {"id": 1, "tool": "read_file", "args": {"path": "README.md"}}
+//! ```
+//!
+//! and read back a correlated response on the same connection:
+//!
+//! ```json
+//! {"id": 1, "result": {...}}
+//! ```
+//! or
+//! ```json
+//! {"id": 1, "error": "tool not found"}
+//! ```
+//!
+//! The `id` is a per-connection monotonic counter assigned by the sandbox
+//! side; the host echoes it back so a single persistent connection can
+//! multiplex several outstanding calls (JS can issue them concurrently, a
+//! synchronous Python caller just waits for the one id it's blocked on).
+
+use crate::mcp::McpToolExecutor;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// One tool-call request read from the sandbox connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BridgeRequest {
+    id: u64,
+    tool: String,
+    args: Value,
+}
+
+/// One tool-call response written back to the sandbox connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BridgeResponse {
+    id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Listening socket for one [`CodeExecutor::execute`](crate::exec::CodeExecutor::execute)
+/// run, torn down (and its socket file unlinked) when the run completes.
+pub struct McpBridge {
+    socket_path: PathBuf,
+}
+
+impl McpBridge {
+    /// Reserve a unique socket path under `<workspace_root>/.vtcode/`. The
+    /// socket itself isn't bound until [`Self::serve`] runs.
+    pub fn new(workspace_root: &Path) -> Self {
+        let socket_path = workspace_root
+            .join(".vtcode")
+            .join(format!("mcp_{}.sock", Uuid::new_v4()));
+        Self { socket_path }
+    }
+
+    /// The value to set `VTCODE_MCP_SOCKET` to in the sandbox's environment.
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Accept the sandbox's connection and serve tool-call requests until
+    /// `shutdown` fires (on run completion or timeout), then unlink the
+    /// socket file. Each request is dispatched to `mcp_client` concurrently
+    /// so slow tool calls don't block others in flight.
+    #[cfg(unix)]
+    pub async fn serve(
+        &self,
+        mcp_client: Arc<dyn McpToolExecutor>,
+        mut shutdown: oneshot::Receiver<()>,
+    ) -> Result<()> {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+        use tokio::net::UnixListener;
+        use tokio::sync::Mutex;
+
+        if let Some(parent) = self.socket_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .context("failed to create .vtcode directory for MCP bridge socket")?;
+        }
+        let _ = tokio::fs::remove_file(&self.socket_path).await;
+
+        let listener = UnixListener::bind(&self.socket_path)
+            .with_context(|| format!("failed to bind MCP bridge socket at {:?}", self.socket_path))?;
+
+        let result = tokio::select! {
+            _ = &mut shutdown => Ok(()),
+            accepted = listener.accept() => {
+                match accepted {
+                    Ok((stream, _)) => {
+                        let (reader, writer) = stream.into_split();
+                        let mut lines = BufReader::new(reader).lines();
+                        let writer = Arc::new(Mutex::new(writer));
+
+                        loop {
+                            tokio::select! {
+                                _ = &mut shutdown => break,
+                                next_line = lines.next_line() => {
+                                    match next_line {
+                                        Ok(Some(line)) => {
+                                            let mcp_client = mcp_client.clone();
+                                            let writer = writer.clone();
+                                            tokio::spawn(async move {
+                                                let response = dispatch(&line, &mcp_client).await;
+                                                if let Ok(payload) = serde_json::to_string(&response) {
+                                                    let mut w = writer.lock().await;
+                                                    if w.write_all(payload.as_bytes()).await.is_ok() {
+                                                        let _ = w.write_all(b"\n").await;
+                                                    }
+                                                }
+                                            });
+                                        }
+                                        Ok(None) => break,
+                                        Err(err) => {
+                                            warn!(error = %err, "MCP bridge connection read failed");
+                                            break;
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        Ok(())
+                    }
+                    Err(err) => Err(err).context("failed to accept MCP bridge connection"),
+                }
+            }
+        };
+
+        let _ = tokio::fs::remove_file(&self.socket_path).await;
+        result
+    }
+
+    #[cfg(not(unix))]
+    pub async fn serve(
+        &self,
+        _mcp_client: Arc<dyn McpToolExecutor>,
+        mut shutdown: oneshot::Receiver<()>,
+    ) -> Result<()> {
+        // No FIFO/named-pipe fallback: return straight away without waiting
+        // for shutdown, so no tool calls can be served.
+        warn!("MCP tool bridge is only implemented for Unix domain sockets on this platform");
+        let _ = &mut shutdown;
+        Ok(())
+    }
+}
+
+async fn dispatch(line: &str, mcp_client: &Arc<dyn McpToolExecutor>) -> BridgeResponse {
+    let request: BridgeRequest = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(err) => {
+            return BridgeResponse {
+                id: 0,
+                result: None,
+                error: Some(format!("malformed tool request: {err}")),
+            };
+        }
+    };
+
+    debug!(tool = %request.tool, id = request.id, "dispatching bridged tool call");
+
+    match mcp_client.execute_tool(&request.tool, request.args).await {
+        Ok(result) => BridgeResponse {
+            id: request.id,
+            result: Some(result),
+            error: None,
+        },
+        Err(err) => BridgeResponse {
+            id: request.id,
+            result: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
@@ -30,22 +30,28 @@
 //! ```
 
 use crate::exec::async_command::{AsyncProcessRunner, ProcessOptions, StreamCaptureConfig};
+use crate::exec::bridge::McpBridge;
 use crate::mcp::McpToolExecutor;
 use crate::sandbox::SandboxProfile;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{debug, info};
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn};
 
 /// Supported languages for code execution.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Language {
     Python3,
+    /// JavaScript run by shelling out to a `node` subprocess.
     JavaScript,
+    /// JavaScript run in-process in an embedded V8 isolate; see
+    /// [`crate::exec::embedded_js`]. Has no external interpreter binary.
+    JavaScriptEmbedded,
 }
 
 impl Language {
@@ -53,13 +59,20 @@ impl Language {
         match self {
             Self::Python3 => "python3",
             Self::JavaScript => "javascript",
+            Self::JavaScriptEmbedded => "javascript_embedded",
         }
     }
 
+    /// The external interpreter binary to spawn. Panics for
+    /// [`Self::JavaScriptEmbedded`], which never spawns a subprocess;
+    /// callers must branch on the language before reaching for this.
     pub fn interpreter(&self) -> &'static str {
         match self {
             Self::Python3 => "python3",
             Self::JavaScript => "node",
+            Self::JavaScriptEmbedded => {
+                unreachable!("JavaScriptEmbedded has no external interpreter binary")
+            }
         }
     }
 }
@@ -106,7 +119,6 @@ impl Default for ExecutionConfig {
 /// Code executor for running agent code in sandboxed environment.
 pub struct CodeExecutor {
     language: Language,
-    #[allow(dead_code)]
     sandbox_profile: SandboxProfile,
     mcp_client: Arc<dyn McpToolExecutor>,
     config: ExecutionConfig,
@@ -153,6 +165,20 @@ impl CodeExecutor {
             "Executing code snippet"
         );
 
+        if matches!(self.language, Language::JavaScriptEmbedded) {
+            let sdk = self
+                .generate_sdk()
+                .await
+                .context("failed to generate embedded JS SDK")?;
+            return crate::exec::embedded_js::execute(
+                code,
+                &sdk,
+                &self.config,
+                self.mcp_client.clone(),
+            )
+            .await;
+        }
+
         let start = Instant::now();
 
         // Generate the SDK wrapper
@@ -163,6 +189,9 @@ impl CodeExecutor {
         let complete_code = match self.language {
             Language::Python3 => self.prepare_python_code(&sdk, code)?,
             Language::JavaScript => self.prepare_javascript_code(&sdk, code)?,
+            Language::JavaScriptEmbedded => {
+                unreachable!("handled by the early embedded_js::execute return above")
+            }
         };
 
         // Write code to temporary file in workspace
@@ -178,22 +207,58 @@ impl CodeExecutor {
             "Wrote code to temporary file"
         );
 
+        // Stand up the host<->sandbox MCP tool bridge so `_call_tool`/`callTool`
+        // in the generated SDK can reach real tools instead of raising
+        // `NotImplementedError`.
+        let bridge = McpBridge::new(&self.workspace_root);
+
         // Execute code via ProcessRunner with timeout
         let mut env = HashMap::new();
-        
+
         // Set workspace path for scripts
         env.insert(
             OsString::from("VTCODE_WORKSPACE"),
             OsString::from(self.workspace_root.to_string_lossy().to_string()),
         );
+        env.insert(
+            OsString::from("VTCODE_MCP_SOCKET"),
+            OsString::from(bridge.socket_path().to_string_lossy().to_string()),
+        );
+
+        let mut program = self.language.interpreter().to_string();
+        let mut args = vec![code_file.to_string_lossy().to_string()];
+        #[cfg(target_os = "linux")]
+        let pre_exec_hook = Some(Box::new(crate::exec::resource_limits::linux_pre_exec_hook(
+            self.config.clone(),
+            self.sandbox_profile.clone(),
+            self.workspace_root.clone(),
+        )) as Box<dyn Fn() -> std::io::Result<()> + Send + Sync>);
+        #[cfg(target_os = "macos")]
+        let pre_exec_hook: Option<Box<dyn Fn() -> std::io::Result<()> + Send + Sync>> = {
+            let profile = crate::exec::resource_limits::macos_sandbox_profile(
+                &self.config,
+                &self.workspace_root,
+            );
+            let (wrapped_program, wrapped_args) =
+                crate::exec::resource_limits::wrap_with_sandbox_exec(program, args, &profile);
+            program = wrapped_program;
+            args = wrapped_args;
+            None
+        };
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        let pre_exec_hook: Option<Box<dyn Fn() -> std::io::Result<()> + Send + Sync>> = {
+            crate::exec::resource_limits::warn_unsupported_platform();
+            None
+        };
 
         let options = ProcessOptions {
-            program: self.language.interpreter().to_string(),
-            args: vec![code_file.to_string_lossy().to_string()],
+            program,
+            args,
             env,
             current_dir: Some(self.workspace_root.clone()),
             timeout: Some(std::time::Duration::from_secs(self.config.timeout_secs)),
             cancellation_token: None,
+            pre_exec_hook,
             stdout: StreamCaptureConfig {
                 capture: true,
                 max_bytes: self.config.max_output_bytes,
@@ -204,8 +269,33 @@ impl CodeExecutor {
             },
         };
 
-        let process_output = AsyncProcessRunner::run(options).await
-            .context("failed to execute code")?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let bridge_mcp_client = self.mcp_client.clone();
+        // Spawned detached, as `session.rs`'s persistent `CodeSession` does:
+        // the generated SDK connects to the bridge socket lazily on first
+        // tool call, so a snippet that never calls a tool would otherwise
+        // leave `listener.accept()` pending for the whole timeout even
+        // though the process itself already finished successfully.
+        tokio::spawn(async move {
+            if let Err(err) = bridge.serve(bridge_mcp_client, shutdown_rx).await {
+                warn!(error = %err, "MCP bridge ended with an error");
+            }
+        });
+
+        let overall_timeout = std::time::Duration::from_secs(self.config.timeout_secs);
+        let process_result = match tokio::time::timeout(overall_timeout, AsyncProcessRunner::run(options)).await {
+            Ok(result) => result,
+            Err(_) => {
+                let _ = shutdown_tx.send(());
+                return Err(anyhow!(
+                    "code execution timed out after {}s",
+                    self.config.timeout_secs
+                ));
+            }
+        };
+        let _ = shutdown_tx.send(());
+
+        let process_output = process_result.context("failed to execute code")?;
 
         let duration_ms = start.elapsed().as_millis();
 
@@ -289,9 +379,38 @@ impl CodeExecutor {
         match self.language {
             Language::Python3 => self.generate_python_sdk().await,
             Language::JavaScript => self.generate_javascript_sdk().await,
+            Language::JavaScriptEmbedded => self.generate_embedded_js_sdk().await,
         }
     }
 
+    /// Generate JS tool wrappers for the embedded runtime: thin functions
+    /// over the `callTool` global that the SDK's first lines bind directly to
+    /// the `op_call_tool` op, rather than over a socket-backed `mcp` object.
+    async fn generate_embedded_js_sdk(&self) -> Result<String> {
+        debug!("Generating embedded JS SDK for MCP tools");
+
+        let tools = self
+            .mcp_client
+            .list_mcp_tools()
+            .await
+            .context("failed to list MCP tools")?;
+
+        let mut sdk = String::from(
+            "// MCP Tools SDK (embedded runtime) - Auto-generated\n\
+             globalThis.callTool = (name, args) => Deno.core.ops.op_call_tool(name, args);\n\
+             function log(message) { console.log(`[LOG] ${message}`); }\n\n",
+        );
+
+        for tool in tools {
+            sdk.push_str(&format!(
+                "async function {}(args = {{}}) {{\n  // {}\n  return await callTool('{}', args);\n}}\n\n",
+                sanitize_function_name(&tool.name), tool.description, tool.name
+            ));
+        }
+
+        Ok(sdk)
+    }
+
     /// Generate Python SDK with MCP tool wrappers.
     async fn generate_python_sdk(&self) -> Result<String> {
         debug!("Generating Python SDK for MCP tools");
@@ -304,26 +423,57 @@ impl CodeExecutor {
         let mut sdk = String::from(
             r#"# MCP Tools SDK - Auto-generated
 import json
+import os
+import socket
 import sys
 from typing import Any, Dict, Optional
 
+class MCPToolError(Exception):
+    """Raised when a bridged MCP tool call fails."""
+
+
 class MCPTools:
-    """Interface to MCP tools from agent code."""
-    
+    """Interface to MCP tools from agent code, bridged over VTCODE_MCP_SOCKET."""
+
     def __init__(self):
         self._call_count = 0
         self._results = []
-    
+        self._socket_path = os.environ.get("VTCODE_MCP_SOCKET")
+        self._conn = None
+
+    def _connection(self):
+        if self._conn is None:
+            if not self._socket_path:
+                raise MCPToolError("VTCODE_MCP_SOCKET is not set; no tool bridge available")
+            self._conn = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+            self._conn.connect(self._socket_path)
+            self._conn_file = self._conn.makefile("rwb")
+        return self._conn_file
+
     def _call_tool(self, name: str, args: Dict[str, Any]) -> Any:
-        """Call an MCP tool and track execution."""
-        # TODO: Implement tool invocation
-        # Should use a side-channel (e.g., file-based IPC) to call tools
-        raise NotImplementedError(f"Tool {name} not available")
-    
+        """Call an MCP tool over the host bridge and wait for its response."""
+        self._call_count += 1
+        request_id = self._call_count
+        conn_file = self._connection()
+        conn_file.write((json.dumps({"id": request_id, "tool": name, "args": args}) + "\n").encode())
+        conn_file.flush()
+
+        while True:
+            line = conn_file.readline()
+            if not line:
+                raise MCPToolError(f"MCP bridge closed before responding to {name}")
+            response = json.loads(line)
+            if response.get("id") != request_id:
+                # Stale response for a call we've already given up on; ignore it.
+                continue
+            if "error" in response:
+                raise MCPToolError(f"{name}: {response['error']}")
+            return response.get("result")
+
     def log(self, message: str) -> None:
         """Log a message that will be captured."""
         print(f"[LOG] {message}")
-    
+
     def set_result(self, data: Any) -> None:
         """Set the result to be returned to the agent."""
         self._results.append(data)
@@ -355,15 +505,65 @@ mcp = MCPTools()
 
         let mut sdk = String::from(
             r#"// MCP Tools SDK - Auto-generated
+const net = require('net');
+
 class MCPTools {
   constructor() {
     this.callCount = 0;
     this.results = [];
+    this.pending = new Map();
+    this.socketPath = process.env.VTCODE_MCP_SOCKET;
+    this.conn = null;
+    this.connectPromise = null;
+  }
+
+  _connect() {
+    if (!this.socketPath) {
+      return Promise.reject(new Error('VTCODE_MCP_SOCKET is not set; no tool bridge available'));
+    }
+    if (!this.connectPromise) {
+      this.connectPromise = new Promise((resolve, reject) => {
+        const conn = net.createConnection(this.socketPath);
+        let buffer = '';
+        conn.on('connect', () => resolve(conn));
+        conn.on('error', (err) => {
+          for (const { reject: rejectPending } of this.pending.values()) {
+            rejectPending(err);
+          }
+          this.pending.clear();
+          reject(err);
+        });
+        conn.on('data', (chunk) => {
+          buffer += chunk.toString('utf8');
+          let newlineIndex;
+          while ((newlineIndex = buffer.indexOf('\n')) !== -1) {
+            const line = buffer.slice(0, newlineIndex);
+            buffer = buffer.slice(newlineIndex + 1);
+            if (!line) continue;
+            const response = JSON.parse(line);
+            const waiter = this.pending.get(response.id);
+            if (!waiter) continue; // stale response for an abandoned call
+            this.pending.delete(response.id);
+            if ('error' in response) {
+              waiter.reject(new Error(response.error));
+            } else {
+              waiter.resolve(response.result);
+            }
+          }
+        });
+      });
+      this.conn = this.connectPromise;
+    }
+    return this.connectPromise;
   }
 
   async callTool(name, args = {}) {
-    // TODO: Implement tool invocation via side-channel
-    throw new Error(`Tool ${name} not available`);
+    const conn = await this._connect();
+    const id = ++this.callCount;
+    return new Promise((resolve, reject) => {
+      this.pending.set(id, { resolve, reject });
+      conn.write(JSON.stringify({ id, tool: name, args }) + '\n');
+    });
   }
 
   log(message) {
@@ -0,0 +1,144 @@
+//! In-process JavaScript execution for [`Language::JavaScriptEmbedded`](crate::exec::code_executor::Language::JavaScriptEmbedded),
+//! used instead of shelling out to `node` and bridging tool calls over a
+//! Unix socket (see [`crate::exec::bridge`]).
+//!
+//! MCP tool calls are registered as a native op (`op_call_tool`) on the
+//! isolate, so the generated `callTool(name, args)` wrapper resolves
+//! directly against [`McpToolExecutor::execute_tool`] as a zero-copy op
+//! dispatch rather than a round trip through a subprocess socket. The
+//! `result` global is read back out of the isolate after evaluation
+//! completes instead of parsing `__JSON_RESULT__`/`__END_JSON__` markers
+//! out of captured stdout.
+
+use crate::exec::code_executor::{ExecutionConfig, ExecutionResult};
+use crate::mcp::McpToolExecutor;
+use anyhow::{Context, Result};
+use deno_core::{op2, JsRuntime, OpState, RuntimeOptions};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// State put on the isolate so `op_call_tool` can reach the host's MCP
+/// client without capturing it in the op's generated signature.
+struct OpBridgeState {
+    mcp_client: Arc<dyn McpToolExecutor>,
+}
+
+#[op2(async)]
+#[serde]
+async fn op_call_tool(
+    state: Rc<RefCell<OpState>>,
+    #[string] name: String,
+    #[serde] args: Value,
+) -> std::result::Result<Value, deno_core::error::AnyError> {
+    let mcp_client = state.borrow().borrow::<OpBridgeState>().mcp_client.clone();
+    mcp_client
+        .execute_tool(&name, args)
+        .await
+        .map_err(|err| deno_core::error::generic_error(err.to_string()))
+}
+
+deno_core::extension!(
+    vtcode_mcp_bridge,
+    ops = [op_call_tool],
+    options = { mcp_client: Arc<dyn McpToolExecutor> },
+    state = |state, options| {
+        state.put(OpBridgeState { mcp_client: options.mcp_client });
+    },
+);
+
+/// Run `code` (with `sdk` tool wrappers prepended) to completion in a fresh
+/// embedded isolate. `config.timeout_secs` is enforced by terminating the
+/// isolate from a watchdog task rather than killing a child process, and a
+/// captured `result` whose JSON exceeds `config.max_output_bytes` fails the
+/// run with an error.
+pub async fn execute(
+    code: &str,
+    sdk: &str,
+    config: &ExecutionConfig,
+    mcp_client: Arc<dyn McpToolExecutor>,
+) -> Result<ExecutionResult> {
+    let start = Instant::now();
+    let timeout = Duration::from_secs(config.timeout_secs);
+
+    let extension = vtcode_mcp_bridge::init_ops_and_esm(mcp_client);
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+        extensions: vec![extension],
+        ..Default::default()
+    });
+
+    // Mirrors the subprocess backend's `kill` on timeout: arm a watchdog
+    // that terminates the isolate if the snippet runs past `timeout`.
+    let isolate_handle = runtime.v8_isolate().thread_safe_handle();
+    let watchdog = tokio::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        isolate_handle.terminate_execution();
+    });
+
+    let bootstrap_result = runtime
+        .execute_script("vtcode:sdk", sdk.to_string())
+        .context("failed to install generated tool wrappers");
+
+    let snippet_result = bootstrap_result.and_then(|_| {
+        runtime
+            .execute_script("vtcode:snippet", code.to_string())
+            .context("embedded JS execution failed")
+    });
+
+    let event_loop_result =
+        tokio::time::timeout(timeout, runtime.run_event_loop(Default::default())).await;
+    watchdog.abort();
+
+    if event_loop_result.is_err() {
+        anyhow::bail!(
+            "embedded JS execution timed out after {}s",
+            config.timeout_secs
+        );
+    }
+    event_loop_result
+        .unwrap()
+        .context("embedded JS event loop failed")?;
+    snippet_result?;
+
+    let json_result = capture_result_global(&mut runtime, config.max_output_bytes)?;
+
+    Ok(ExecutionResult {
+        exit_code: 0,
+        stdout: String::new(),
+        stderr: String::new(),
+        json_result,
+        duration_ms: start.elapsed().as_millis(),
+    })
+}
+
+/// Read `globalThis.result` back out of the isolate as JSON, honoring
+/// `max_output_bytes` on its serialized size.
+fn capture_result_global(runtime: &mut JsRuntime, max_output_bytes: usize) -> Result<Option<Value>> {
+    let capture = runtime
+        .execute_script(
+            "vtcode:capture",
+            "typeof result === 'undefined' ? null : JSON.stringify(result)".to_string(),
+        )
+        .context("failed to read back the session `result` global")?;
+
+    let scope = &mut runtime.handle_scope();
+    let local = deno_core::v8::Local::new(scope, capture);
+    if local.is_null_or_undefined() {
+        return Ok(None);
+    }
+
+    let json_str = local.to_rust_string_lossy(scope);
+    if json_str.len() > max_output_bytes {
+        anyhow::bail!(
+            "embedded JS result exceeded max_output_bytes ({} > {})",
+            json_str.len(),
+            max_output_bytes
+        );
+    }
+
+    serde_json::from_str(&json_str)
+        .map(Some)
+        .context("failed to parse embedded JS result as JSON")
+}
@@ -1,8 +1,21 @@
 pub mod async_command;
+pub mod behavior;
+pub mod benchmark;
+pub mod bridge;
 pub mod cancellation;
 pub mod code_executor;
+pub mod embedded_js;
 pub mod events;
+pub mod resource_limits;
 pub mod sdk_ipc;
+pub mod session;
 
+pub use behavior::{
+    AgentBehaviorAnalyzer, FailurePatterns, FailureSink, FileFailureSink, RemoteFailureSink,
+    SkillStats, ToolFailureEvent, ToolStats,
+};
+pub use benchmark::{BenchmarkReport, BenchmarkScenario, ExecutionBenchmark, ScenarioResult};
+pub use bridge::McpBridge;
 pub use code_executor::{CodeExecutor, ExecutionConfig, ExecutionResult, Language};
 pub use sdk_ipc::{ToolIpcHandler, ToolRequest, ToolResponse};
+pub use session::CodeSession;
@@ -0,0 +1,165 @@
+//! Translates a [`SandboxProfile`] and [`ExecutionConfig`] into the OS
+//! primitives that actually contain a spawned interpreter, instead of the
+//! profile sitting unused while executed code keeps the parent's full
+//! filesystem and network access.
+//!
+//! - Linux: a `pre_exec` hook (installed on [`ProcessOptions`](crate::exec::async_command::ProcessOptions)'s
+//!   new `pre_exec_hook` field, and directly via `CommandExt::pre_exec` for
+//!   [`CodeSession`](crate::exec::session::CodeSession)'s long-lived child)
+//!   sets `RLIMIT_AS` from `memory_limit_mb` and `RLIMIT_CPU` from
+//!   `timeout_secs`, installs a Landlock ruleset that restricts writes to
+//!   `<workspace>/.vtcode` and reads to the workspace root, and, when
+//!   `allow_network` is false, unshares into a fresh network namespace.
+//! - macOS: a `sandbox-exec` profile generated from the same inputs, with
+//!   the interpreter launched under `sandbox-exec -p <profile> --`.
+//! - Anything else: sandboxing is a no-op; `CodeExecutor`/`CodeSession` log
+//!   on every run or spawn that code is running unconfined.
+
+use crate::exec::code_executor::ExecutionConfig;
+use crate::sandbox::SandboxProfile;
+use std::path::Path;
+use tracing::warn;
+
+/// Build the `pre_exec` closure that applies `config`/`profile` limits to a
+/// child process between `fork` and `exec`. Only async-signal-safe calls are
+/// allowed in the closure body, per `std::os::unix::process::CommandExt::pre_exec`'s
+/// safety contract.
+#[cfg(target_os = "linux")]
+pub fn linux_pre_exec_hook(
+    config: ExecutionConfig,
+    profile: SandboxProfile,
+    workspace_root: std::path::PathBuf,
+) -> impl Fn() -> std::io::Result<()> + Send + Sync + 'static {
+    move || {
+        apply_rlimits(&config)?;
+        apply_landlock(&profile, &workspace_root);
+        if !config.allow_network {
+            unshare_network_namespace();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn apply_rlimits(config: &ExecutionConfig) -> std::io::Result<()> {
+    let as_bytes = config.memory_limit_mb.saturating_mul(1024 * 1024);
+    let as_limit = libc::rlimit {
+        rlim_cur: as_bytes,
+        rlim_max: as_bytes,
+    };
+    // SAFETY: `setrlimit` is async-signal-safe and `as_limit` is a valid,
+    // fully-initialized `rlimit` value.
+    if unsafe { libc::setrlimit(libc::RLIMIT_AS, &as_limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let cpu_limit = libc::rlimit {
+        rlim_cur: config.timeout_secs,
+        rlim_max: config.timeout_secs,
+    };
+    // SAFETY: same as above.
+    if unsafe { libc::setrlimit(libc::RLIMIT_CPU, &cpu_limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Restrict filesystem access via Landlock: read access to the whole
+/// workspace, write access only under `<workspace>/.vtcode`. Failures are
+/// swallowed (not surfaced as a hard error) because Landlock is unavailable
+/// on kernels older than 5.13 and sandboxing should degrade, not crash the
+/// run, on an unsupported host.
+#[cfg(target_os = "linux")]
+fn apply_landlock(profile: &SandboxProfile, workspace_root: &Path) {
+    use landlock::{
+        Access, AccessFs, Ruleset, RulesetAttr, RulesetCreatedAttr, RulesetStatus, ABI,
+    };
+
+    let scratch_dir = workspace_root.join(".vtcode");
+    let result: Result<RulesetStatus, landlock::RulesetError> = (|| {
+        let read_access = AccessFs::from_read(ABI::V1);
+        let write_access = AccessFs::from_all(ABI::V1);
+
+        let ruleset = Ruleset::default()
+            .handle_access(read_access)?
+            .handle_access(write_access)?
+            .create()?
+            .add_rule(landlock::PathBeneath::new(
+                landlock::PathFd::new(workspace_root)?,
+                read_access,
+            ))?
+            .add_rule(landlock::PathBeneath::new(
+                landlock::PathFd::new(&scratch_dir)?,
+                write_access,
+            ))?;
+
+        let _ = profile;
+        Ok(ruleset.restrict_self()?.ruleset_status)
+    })();
+
+    match result {
+        Ok(RulesetStatus::FullyEnforced) => {}
+        Ok(status) => {
+            warn!(?status, "Landlock ruleset only partially enforced");
+        }
+        Err(err) => {
+            warn!(error = %err, "failed to apply Landlock ruleset; continuing unconfined");
+        }
+    }
+}
+
+/// Drop the child into a fresh, unconfigured network namespace, which has no
+/// interfaces other than loopback and so can't reach the network.
+#[cfg(target_os = "linux")]
+fn unshare_network_namespace() {
+    // SAFETY: `unshare` is async-signal-safe; a failure here just leaves
+    // network access in place, which is logged below and is not fatal to
+    // the run.
+    if unsafe { libc::unshare(libc::CLONE_NEWNET) } != 0 {
+        warn!(
+            error = %std::io::Error::last_os_error(),
+            "failed to unshare network namespace; network access remains available"
+        );
+    }
+}
+
+/// Generate a `sandbox-exec` profile (Apple's deprecated but still
+/// functional Seatbelt DSL) enforcing the same shape of restriction as the
+/// Linux Landlock path: deny by default, allow reads under the workspace,
+/// allow writes only under `<workspace>/.vtcode`, and deny network unless
+/// `allow_network` is set.
+#[cfg(target_os = "macos")]
+pub fn macos_sandbox_profile(config: &ExecutionConfig, workspace_root: &Path) -> String {
+    let workspace = workspace_root.display();
+    let scratch = workspace_root.join(".vtcode");
+    let scratch = scratch.display();
+
+    let network_rule = if config.allow_network {
+        "(allow network*)"
+    } else {
+        "(deny network*)"
+    };
+
+    format!(
+        "(version 1)\n\
+         (deny default)\n\
+         (allow process-fork)\n\
+         (allow file-read* (subpath \"{workspace}\"))\n\
+         (allow file-write* (subpath \"{scratch}\"))\n\
+         {network_rule}\n"
+    )
+}
+
+/// Rewrite `(program, args)` to run under `sandbox-exec -p <profile> --`.
+#[cfg(target_os = "macos")]
+pub fn wrap_with_sandbox_exec(program: String, args: Vec<String>, profile: &str) -> (String, Vec<String>) {
+    let mut wrapped_args = vec!["-p".to_string(), profile.to_string(), program];
+    wrapped_args.extend(args);
+    ("sandbox-exec".to_string(), wrapped_args)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn warn_unsupported_platform() {
+    warn!("no OS-level sandbox enforcement is wired up on this platform; code runs unconfined");
+}
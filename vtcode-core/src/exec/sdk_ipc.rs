@@ -1,12 +1,13 @@
 //! Inter-process communication for calling MCP tools from sandboxed code.
 //!
 //! This module provides a file-based IPC mechanism that allows code running in
-//! a sandbox to call MCP tools. The code writes tool requests to a file, and
-//! the executor reads and processes them, writing back results.
+//! a sandbox to call MCP tools. The caller writes a request file and the
+//! executor writes a matching response file; both sides are woken
+//! immediately by a filesystem watcher rather than polling.
 //!
 //! # Protocol
 //!
-//! Requests (code → executor):
+//! Each request gets its own file at `requests/<id>.json`:
 //! ```json
 //! {
 //!   "id": "uuid",
@@ -15,7 +16,7 @@
 //! }
 //! ```
 //!
-//! Responses (executor → code):
+//! and its reply at `responses/<id>.json`:
 //! ```json
 //! {
 //!   "id": "uuid",
@@ -31,13 +32,22 @@
 //!   "error": "Tool not found"
 //! }
 //! ```
+//!
+//! Per-UUID files (rather than a single shared `request.json`/`response.json`
+//! pair) let many calls be outstanding at once; a `tokio::sync::oneshot`
+//! dispatch map keyed by request id resolves each caller to exactly its own
+//! response as soon as the response watcher observes the file.
 
 use anyhow::{Context, Result};
+use notify::{RecursiveMode, Watcher};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::fs;
-use tokio::time::sleep;
+use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::time::timeout;
 use uuid::Uuid;
 
 /// IPC request from sandboxed code to executor.
@@ -59,75 +69,213 @@ pub struct ToolResponse {
     pub error: Option<String>,
 }
 
-/// IPC handler for tool invocation between code and executor.
+/// Concurrent, event-driven IPC handler for tool invocation between code and
+/// executor. Backed by per-request files under `requests/`/`responses/`
+/// rather than a single shared pair, so many calls can be outstanding at
+/// once; filesystem watchers replace the old busy-poll loop.
+#[derive(Clone)]
 pub struct ToolIpcHandler {
-    ipc_dir: PathBuf,
+    requests_dir: PathBuf,
+    responses_dir: PathBuf,
+    /// Callers awaiting a response for a given request id, fulfilled by the
+    /// response watcher task as soon as it observes the matching file.
+    pending: Arc<Mutex<HashMap<String, oneshot::Sender<ToolResponse>>>>,
+    /// Requests observed by the request watcher, drained by `next_request`.
+    incoming: Arc<Mutex<mpsc::UnboundedReceiver<ToolRequest>>>,
 }
 
 impl ToolIpcHandler {
-    /// Create a new IPC handler with the given directory.
-    pub fn new(ipc_dir: PathBuf) -> Self {
-        Self { ipc_dir }
-    }
-
-    /// Read a tool request from the code.
-    pub async fn read_request(&self) -> Result<Option<ToolRequest>> {
-        let request_file = self.ipc_dir.join("request.json");
-
-        if !request_file.exists() {
-            return Ok(None);
-        }
-
-        let content = fs::read_to_string(&request_file)
+    /// Create a new IPC handler rooted at `ipc_dir`, creating its
+    /// `requests/`/`responses/` subdirectories and starting the background
+    /// watchers that back `call`/`next_request`.
+    pub async fn new(ipc_dir: PathBuf) -> Result<Self> {
+        let requests_dir = ipc_dir.join("requests");
+        let responses_dir = ipc_dir.join("responses");
+        fs::create_dir_all(&requests_dir)
             .await
-            .context("failed to read request file")?;
+            .context("failed to create IPC requests directory")?;
+        fs::create_dir_all(&responses_dir)
+            .await
+            .context("failed to create IPC responses directory")?;
 
-        let request: ToolRequest = serde_json::from_str(&content)
-            .context("failed to parse request JSON")?;
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+        let (request_tx, request_rx) = mpsc::unbounded_channel();
 
-        // Clean up request file
-        let _ = fs::remove_file(&request_file).await;
+        spawn_watcher(&responses_dir, {
+            let pending = pending.clone();
+            move |path| {
+                let pending = pending.clone();
+                async move {
+                    let Ok(content) = fs::read_to_string(&path).await else {
+                        return;
+                    };
+                    let Ok(response) = serde_json::from_str::<ToolResponse>(&content) else {
+                        return;
+                    };
+                    let _ = fs::remove_file(&path).await;
+                    if let Some(sender) = pending.lock().await.remove(&response.id) {
+                        let _ = sender.send(response);
+                    }
+                }
+            }
+        })
+        .context("failed to watch IPC responses directory")?;
 
-        Ok(Some(request))
+        spawn_watcher(&requests_dir, move |path| {
+            let request_tx = request_tx.clone();
+            async move {
+                let Ok(content) = fs::read_to_string(&path).await else {
+                    return;
+                };
+                let Ok(request) = serde_json::from_str::<ToolRequest>(&content) else {
+                    return;
+                };
+                let _ = fs::remove_file(&path).await;
+                let _ = request_tx.send(request);
+            }
+        })
+        .context("failed to watch IPC requests directory")?;
+
+        Ok(Self {
+            requests_dir,
+            responses_dir,
+            pending,
+            incoming: Arc::new(Mutex::new(request_rx)),
+        })
     }
 
-    /// Write a tool response back to the code.
-    pub async fn write_response(&self, response: ToolResponse) -> Result<()> {
-        let response_file = self.ipc_dir.join("response.json");
+    /// Caller side: write `requests/<id>.json` and wait for the executor's
+    /// `responses/<id>.json`, resolved the instant the response watcher
+    /// observes it rather than by polling.
+    pub async fn call(&self, tool_name: &str, args: Value, wait_timeout: Duration) -> Result<ToolResponse> {
+        let id = Self::new_request_id();
+        let request = ToolRequest {
+            id: id.clone(),
+            tool_name: tool_name.to_string(),
+            args,
+        };
 
-        let json = serde_json::to_string(&response)
-            .context("failed to serialize response")?;
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id.clone(), tx);
 
-        fs::write(&response_file, json)
+        let path = self.requests_dir.join(format!("{id}.json"));
+        let json = serde_json::to_string(&request).context("failed to serialize request")?;
+        fs::write(&path, json)
             .await
-            .context("failed to write response file")?;
+            .context("failed to write request file")?;
 
-        Ok(())
-    }
-
-    /// Wait for a request with timeout.
-    pub async fn wait_for_request(&self, timeout: Duration) -> Result<Option<ToolRequest>> {
-        let start = std::time::Instant::now();
-
-        loop {
-            if let Some(request) = self.read_request().await? {
-                return Ok(Some(request));
+        match timeout(wait_timeout, rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => {
+                self.pending.lock().await.remove(&id);
+                anyhow::bail!("IPC response channel closed before a reply for {id}")
             }
-
-            if start.elapsed() > timeout {
-                return Ok(None);
+            Err(_) => {
+                self.pending.lock().await.remove(&id);
+                anyhow::bail!("timed out waiting for IPC response to {id}")
             }
+        }
+    }
 
-            sleep(Duration::from_millis(100)).await;
+    /// Executor side: wait for the next request observed by the request
+    /// watcher, or `None` if `wait_timeout` elapses with none arriving.
+    pub async fn next_request(&self, wait_timeout: Duration) -> Result<Option<ToolRequest>> {
+        let mut incoming = self.incoming.lock().await;
+        match timeout(wait_timeout, incoming.recv()).await {
+            Ok(Some(request)) => Ok(Some(request)),
+            Ok(None) => anyhow::bail!("IPC request channel closed"),
+            Err(_) => Ok(None),
         }
     }
 
+    /// Executor side: write `responses/<id>.json` for a request handled via
+    /// `next_request`.
+    pub async fn respond(&self, response: ToolResponse) -> Result<()> {
+        let path = self.responses_dir.join(format!("{}.json", response.id));
+        let json = serde_json::to_string(&response).context("failed to serialize response")?;
+        fs::write(&path, json)
+            .await
+            .context("failed to write response file")?;
+        Ok(())
+    }
+
+    /// Remove request files older than `max_age` that were never picked up
+    /// or answered, so a dead sandbox's unanswered calls don't accumulate.
+    pub async fn cleanup_orphaned_requests(&self, max_age: Duration) -> Result<usize> {
+        cleanup_stale_files(&self.requests_dir, max_age).await
+    }
+
     /// Create a request ID.
     pub fn new_request_id() -> String {
         Uuid::new_v4().to_string()
     }
 }
 
+/// Watch `dir` (non-recursively) for created/modified `.json` files, running
+/// `on_file` for each one. The watcher is kept alive for the process
+/// lifetime by the spawned task that owns it.
+fn spawn_watcher<F, Fut>(dir: &std::path::Path, on_file: F) -> Result<()>
+where
+    F: Fn(PathBuf) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel::<notify::Event>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        if let Ok(event) = event {
+            let _ = tx.send(event);
+        }
+    })
+    .context("failed to create filesystem watcher")?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .with_context(|| format!("failed to watch {dir:?}"))?;
+
+    tokio::spawn(async move {
+        let _watcher = watcher;
+        while let Some(event) = rx.recv().await {
+            if !matches!(
+                event.kind,
+                notify::EventKind::Create(_) | notify::EventKind::Modify(_)
+            ) {
+                continue;
+            }
+            for path in event.paths {
+                if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                    continue;
+                }
+                on_file(path).await;
+            }
+        }
+    });
+
+    Ok(())
+}
+
+async fn cleanup_stale_files(dir: &std::path::Path, max_age: Duration) -> Result<usize> {
+    let mut removed = 0;
+    let mut entries = fs::read_dir(dir).await.context("failed to read IPC directory")?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        let Ok(age) = metadata.modified().and_then(|modified| {
+            modified
+                .elapsed()
+                .map_err(|err| std::io::Error::other(err.to_string()))
+        }) else {
+            continue;
+        };
+        if age > max_age && fs::remove_file(&path).await.is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,4 +323,57 @@ mod tests {
         assert!(json.contains("false"));
         assert!(json.contains("File not found"));
     }
+
+    #[tokio::test]
+    async fn call_and_respond_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("vtcode-ipc-test-{}", Uuid::new_v4()));
+        let handler = ToolIpcHandler::new(dir.clone()).await.unwrap();
+
+        let caller = handler.clone();
+        let call = tokio::spawn(async move {
+            caller
+                .call("read_file", json!({"path": "/test"}), Duration::from_secs(5))
+                .await
+        });
+
+        let request = handler
+            .next_request(Duration::from_secs(5))
+            .await
+            .unwrap()
+            .expect("request should arrive");
+        assert_eq!(request.tool_name, "read_file");
+
+        handler
+            .respond(ToolResponse {
+                id: request.id,
+                success: true,
+                result: Some(json!({"ok": true})),
+                error: None,
+            })
+            .await
+            .unwrap();
+
+        let response = call.await.unwrap().unwrap();
+        assert!(response.success);
+
+        let _ = fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn cleanup_removes_stale_request_files() {
+        let dir = std::env::temp_dir().join(format!("vtcode-ipc-cleanup-{}", Uuid::new_v4()));
+        let handler = ToolIpcHandler::new(dir.clone()).await.unwrap();
+
+        let stale_path = dir.join("requests").join("stale.json");
+        fs::write(&stale_path, "{}").await.unwrap();
+
+        let removed = handler
+            .cleanup_orphaned_requests(Duration::from_secs(0))
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(!stale_path.exists());
+
+        let _ = fs::remove_dir_all(&dir).await;
+    }
 }
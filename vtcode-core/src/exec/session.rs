@@ -0,0 +1,416 @@
+//! Persistent REPL-style execution sessions.
+//!
+//! [`CodeExecutor::execute`](crate::exec::code_executor::CodeExecutor::execute)
+//! spawns a fresh interpreter per call, so any variables, imports, or other
+//! state an agent built up are gone on the next call. [`CodeSession`] instead
+//! launches the interpreter once, running a small bootstrap loop that reads
+//! `EXEC:<block_id>:<base64>` lines from stdin (base64 sidesteps newline and
+//! quoting issues in the snippet) and `exec`s/`eval`s each one against the
+//! same persistent globals, so state survives across [`Self::eval`] calls.
+//! Each block ends with a `__BLOCK_DONE__<block_id>__` sentinel on stdout
+//! that `eval` reads for, the same way the one-shot `__JSON_RESULT__`/
+//! `__END_JSON__` markers bracket a captured `result`.
+
+use crate::exec::bridge::McpBridge;
+use crate::exec::code_executor::{CodeExecutor, ExecutionConfig, ExecutionResult, Language};
+use crate::mcp::McpToolExecutor;
+use crate::sandbox::SandboxProfile;
+use anyhow::{anyhow, Context, Result};
+use base64::Engine as _;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+
+/// Bootstrap loop fed to `python3 -u -c`: reads `EXEC:<id>:<base64>` lines
+/// from stdin, `exec`s each against the same module-level globals, captures
+/// a `result` variable the same way one-shot execution does, and always
+/// emits the done marker so a silent block is still detectable.
+const PYTHON_BOOTSTRAP: &str = r#"
+import sys, base64, json
+while True:
+    line = sys.stdin.readline()
+    if not line:
+        break
+    line = line.rstrip("\n")
+    if not line.startswith("EXEC:"):
+        continue
+    block_id, _, code_b64 = line[len("EXEC:"):].partition(":")
+    code = base64.b64decode(code_b64).decode()
+    try:
+        exec(compile(code, "<session>", "exec"), globals())
+        if "result" in globals():
+            print("__JSON_RESULT__")
+            print(json.dumps(globals()["result"], default=str))
+            print("__END_JSON__")
+            del globals()["result"]
+    except Exception as e:
+        print(f"__BLOCK_ERROR__ {type(e).__name__}: {e}")
+    print(f"__BLOCK_DONE__{block_id}__")
+    sys.stdout.flush()
+"#;
+
+/// Bootstrap loop fed to `node -e`: same `EXEC:<id>:<base64>` protocol, run
+/// against one `vm` context that lives as long as the session. Block `0`
+/// (the tool SDK prelude) runs as a plain script, so its top-level
+/// declarations persist in the context. Every later block runs inside an
+/// `async` wrapper so it can `await` tool calls; its declarations are
+/// block-scoped to that wrapper, same as any JS closure — assign to context
+/// properties (no `var`/`let`) for state meant to survive.
+const JAVASCRIPT_BOOTSTRAP: &str = r#"
+const vm = require('vm');
+const readline = require('readline');
+const context = vm.createContext({
+  require, console, process, Buffer, setTimeout, setInterval, clearTimeout, clearInterval,
+});
+const rl = readline.createInterface({ input: process.stdin, terminal: false });
+rl.on('line', (line) => {
+  if (!line.startsWith('EXEC:')) return;
+  const sep = line.indexOf(':', 5);
+  const blockId = line.slice(5, sep);
+  const code = Buffer.from(line.slice(sep + 1), 'base64').toString('utf8');
+  const wrapped = `(async () => {\n${code}\nif (typeof result !== 'undefined') { return result; }\n})()`;
+  Promise.resolve()
+    .then(() => vm.runInContext(blockId === '0' ? code : wrapped, context))
+    .then((value) => {
+      if (value !== undefined) {
+        console.log('__JSON_RESULT__');
+        console.log(JSON.stringify(value, null, 2));
+        console.log('__END_JSON__');
+      }
+    })
+    .catch((err) => {
+      console.log(`__BLOCK_ERROR__ ${err.name || 'Error'}: ${err.message || err}`);
+    })
+    .finally(() => {
+      console.log(`__BLOCK_DONE__${blockId}__`);
+    });
+});
+"#;
+
+/// A long-lived interpreter process fed one block at a time, so state from
+/// one [`Self::eval`] call (variables, imports, intermediate data) is still
+/// there on the next.
+pub struct CodeSession {
+    language: Language,
+    sandbox_profile: SandboxProfile,
+    mcp_client: Arc<dyn McpToolExecutor>,
+    config: ExecutionConfig,
+    workspace_root: PathBuf,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    bridge_shutdown: Option<oneshot::Sender<()>>,
+    next_block_id: u64,
+    /// Set once a block fails to emit its done marker within the per-call
+    /// timeout, or the child exits mid-block; the stdout stream can no
+    /// longer be trusted to be aligned on a block boundary, so every
+    /// subsequent `eval` fails fast until `reset` is called.
+    desynced: bool,
+}
+
+impl CodeSession {
+    /// Spawn the interpreter, bring up its MCP tool bridge, and load the
+    /// generated tool SDK into its persistent globals.
+    pub async fn new(
+        language: Language,
+        sandbox_profile: SandboxProfile,
+        mcp_client: Arc<dyn McpToolExecutor>,
+        workspace_root: PathBuf,
+    ) -> Result<Self> {
+        let config = ExecutionConfig::default();
+        let (child, stdin, stdout, bridge_shutdown) = spawn_interpreter(
+            language,
+            &sandbox_profile,
+            &config,
+            &workspace_root,
+            &mcp_client,
+        )
+        .await?;
+
+        let mut session = Self {
+            language,
+            sandbox_profile,
+            mcp_client,
+            config,
+            workspace_root,
+            child,
+            stdin,
+            stdout,
+            bridge_shutdown: Some(bridge_shutdown),
+            next_block_id: 0,
+            desynced: false,
+        };
+
+        let prelude = session.generate_prelude().await?;
+        session.write_block(&prelude).await?;
+        let prelude_id = session.next_block_id - 1;
+        session
+            .read_until_done(prelude_id, session.config.timeout_secs)
+            .await?;
+
+        Ok(session)
+    }
+
+    /// Set custom execution configuration. `timeout_secs` applies to the
+    /// next `eval`; the OS-level limits apply from the next `reset`, since
+    /// the interpreter is already running. `max_output_bytes` is not applied
+    /// to session output.
+    pub fn with_config(mut self, config: ExecutionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Feed one code block to the running interpreter and return its
+    /// result. Interpreter state (variables, imports) from prior `eval`
+    /// calls is still present.
+    pub async fn eval(&mut self, code: &str) -> Result<ExecutionResult> {
+        if self.desynced {
+            anyhow::bail!(
+                "session is desynced after a prior timeout or crash; call reset() before eval()"
+            );
+        }
+
+        let start = Instant::now();
+        self.write_block(code).await?;
+        let block_id = self.next_block_id - 1;
+
+        let stdout = match self
+            .read_until_done(block_id, self.config.timeout_secs)
+            .await
+        {
+            Ok(stdout) => stdout,
+            Err(err) => {
+                self.desynced = true;
+                return Err(err);
+            }
+        };
+
+        if let Ok(Some(status)) = self.child.try_wait() {
+            self.desynced = true;
+            anyhow::bail!("session interpreter exited mid-block with status {status}");
+        }
+
+        Ok(ExecutionResult {
+            exit_code: 0,
+            json_result: extract_json_result(&stdout),
+            stdout,
+            stderr: String::new(),
+            duration_ms: start.elapsed().as_millis(),
+        })
+    }
+
+    /// Restart the child interpreter and its MCP bridge, discarding all
+    /// session state (globals, pending output).
+    pub async fn reset(&mut self) -> Result<()> {
+        if let Some(shutdown) = self.bridge_shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        let _ = self.child.start_kill();
+        let _ = self.child.wait().await;
+
+        let (child, stdin, stdout, bridge_shutdown) = spawn_interpreter(
+            self.language,
+            &self.sandbox_profile,
+            &self.config,
+            &self.workspace_root,
+            &self.mcp_client,
+        )
+        .await?;
+        self.child = child;
+        self.stdin = stdin;
+        self.stdout = stdout;
+        self.bridge_shutdown = Some(bridge_shutdown);
+        self.next_block_id = 0;
+        self.desynced = false;
+
+        let prelude = self.generate_prelude().await?;
+        self.write_block(&prelude).await?;
+        let prelude_id = self.next_block_id - 1;
+        self.read_until_done(prelude_id, self.config.timeout_secs)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn write_block(&mut self, code: &str) -> Result<()> {
+        let block_id = self.next_block_id;
+        self.next_block_id += 1;
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(code);
+        let line = format!("EXEC:{block_id}:{encoded}\n");
+
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .context("failed to write block to session stdin")?;
+        self.stdin
+            .flush()
+            .await
+            .context("failed to flush session stdin")?;
+        Ok(())
+    }
+
+    /// Read lines from stdout until `__BLOCK_DONE__<block_id>__` appears, or
+    /// `timeout_secs` elapses, or the child closes stdout without emitting
+    /// it.
+    async fn read_until_done(&mut self, block_id: u64, timeout_secs: u64) -> Result<String> {
+        let marker = format!("__BLOCK_DONE__{block_id}__");
+        let deadline = std::time::Duration::from_secs(timeout_secs);
+
+        let read_loop = async {
+            let mut output = String::new();
+            let mut line = String::new();
+            loop {
+                line.clear();
+                let bytes_read = self
+                    .stdout
+                    .read_line(&mut line)
+                    .await
+                    .context("failed to read from session stdout")?;
+                if bytes_read == 0 {
+                    anyhow::bail!("session interpreter closed stdout before emitting done marker");
+                }
+                if line.trim_end() == marker {
+                    return Ok(output);
+                }
+                output.push_str(&line);
+            }
+        };
+
+        tokio::time::timeout(deadline, read_loop)
+            .await
+            .map_err(|_| anyhow!("block did not finish within {timeout_secs}s"))?
+    }
+
+    async fn generate_prelude(&self) -> Result<String> {
+        let executor = CodeExecutor::new(
+            self.language,
+            self.sandbox_profile.clone(),
+            self.mcp_client.clone(),
+            self.workspace_root.clone(),
+        );
+        executor.generate_sdk().await
+    }
+}
+
+/// Spawn the interpreter's bootstrap loop with a fresh MCP bridge, returning
+/// the child, its stdin/stdout handles, and the bridge's shutdown sender.
+/// Applies the same `SandboxProfile`/`ExecutionConfig` OS-level enforcement
+/// as one-shot `CodeExecutor::execute` so a long-lived session is contained
+/// the same way.
+async fn spawn_interpreter(
+    language: Language,
+    sandbox_profile: &SandboxProfile,
+    config: &ExecutionConfig,
+    workspace_root: &PathBuf,
+    mcp_client: &Arc<dyn McpToolExecutor>,
+) -> Result<(Child, ChildStdin, BufReader<ChildStdout>, oneshot::Sender<()>)> {
+    let bridge = McpBridge::new(workspace_root);
+
+    let (mut program, mut args): (String, Vec<String>) = match language {
+        Language::Python3 => (
+            "python3".to_string(),
+            vec!["-u".to_string(), "-c".to_string(), PYTHON_BOOTSTRAP.to_string()],
+        ),
+        Language::JavaScript => (
+            "node".to_string(),
+            vec!["-e".to_string(), JAVASCRIPT_BOOTSTRAP.to_string()],
+        ),
+        Language::JavaScriptEmbedded => {
+            anyhow::bail!(
+                "CodeSession doesn't support JavaScriptEmbedded yet; it has no subprocess to keep alive between calls"
+            );
+        }
+    };
+
+    #[cfg(target_os = "macos")]
+    {
+        let profile = crate::exec::resource_limits::macos_sandbox_profile(config, workspace_root);
+        let (wrapped_program, wrapped_args) =
+            crate::exec::resource_limits::wrap_with_sandbox_exec(program, args, &profile);
+        program = wrapped_program;
+        args = wrapped_args;
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    crate::exec::resource_limits::warn_unsupported_platform();
+
+    let mut command = Command::new(&program);
+    command
+        .args(&args)
+        .current_dir(workspace_root)
+        .env("VTCODE_WORKSPACE", workspace_root.to_string_lossy().to_string())
+        .env(
+            "VTCODE_MCP_SOCKET",
+            bridge.socket_path().to_string_lossy().to_string(),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit());
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::process::CommandExt;
+        let hook = crate::exec::resource_limits::linux_pre_exec_hook(
+            config.clone(),
+            sandbox_profile.clone(),
+            workspace_root.clone(),
+        );
+        // SAFETY: the closure only calls the async-signal-safe `setrlimit`,
+        // Landlock, and `unshare` primitives documented on `linux_pre_exec_hook`.
+        unsafe {
+            command.pre_exec(move || hook());
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = sandbox_profile;
+
+    let mut child = command
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("failed to spawn {} session", language.as_str()))?;
+
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("failed to open session stdin"))?;
+    let stdout = BufReader::new(
+        child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("failed to open session stdout"))?,
+    );
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+    let bridge_mcp_client = mcp_client.clone();
+    tokio::spawn(async move {
+        if let Err(err) = bridge.serve(bridge_mcp_client, shutdown_rx).await {
+            warn!(error = %err, "session MCP bridge ended with an error");
+        }
+    });
+
+    Ok((child, stdin, stdout, shutdown_tx))
+}
+
+/// Pull the `__JSON_RESULT__`/`__END_JSON__`-delimited JSON payload out of a
+/// block's captured stdout, if it emitted one.
+fn extract_json_result(output: &str) -> Option<serde_json::Value> {
+    let start_marker = "__JSON_RESULT__";
+    let end_marker = "__END_JSON__";
+
+    let start = output.find(start_marker)? + start_marker.len();
+    let end = output[start..].find(end_marker)? + start;
+    let json_str = output[start..end].trim();
+
+    match serde_json::from_str(json_str) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            debug!(error = %err, "failed to parse session block JSON result");
+            None
+        }
+    }
+}
@@ -5,6 +5,44 @@
 
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use vtcode_acp_client::error::AcpResult;
+
+/// Micro-cents per cent. All internal cost math is done in integer
+/// micro-cents so summing thousands of per-call costs can't drift the way
+/// repeated `f64` cent arithmetic does; `f64` is only used at the display
+/// boundary (`format_cost_dollars`) and in compatibility getters.
+pub(crate) const MICRO_CENTS_PER_CENT: u64 = 1_000_000;
+
+/// Convert a whole-cent amount to exact micro-cents, for defining pricing
+/// table constants below
+const fn cents(whole_cents: u64) -> u64 {
+    whole_cents * MICRO_CENTS_PER_CENT
+}
+
+/// Round `numerator / denominator` to the nearest integer (half up)
+fn div_round(numerator: u128, denominator: u128) -> u128 {
+    (numerator + denominator / 2) / denominator
+}
+
+/// How capable a model is, for filtering cost-driven recommendations so they
+/// don't downgrade a task to a model too weak to do it. Declared weakest to
+/// strongest so `CapabilityTier` derives the natural `Basic < Standard <
+/// Advanced` ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CapabilityTier {
+    /// Cheap/free-tier models suitable for trivial or high-volume tasks
+    Basic,
+    /// General-purpose models suitable for most day-to-day tasks
+    Standard,
+    /// Frontier models reserved for tasks that need the extra capability
+    Advanced,
+}
+
+impl Default for CapabilityTier {
+    fn default() -> Self {
+        Self::Standard
+    }
+}
 
 /// Pricing information for a specific model
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,16 +51,37 @@ pub struct ModelPricing {
     pub model_id: String,
     /// Provider name (openai, anthropic, google)
     pub provider: String,
-    /// Cost per 1M input tokens (in cents)
-    pub input_cost_per_1m: f64,
-    /// Cost per 1M output tokens (in cents)
-    pub output_cost_per_1m: f64,
-    /// Minimum cost per request (in cents, defaults to 0)
-    pub minimum_cost: f64,
+    /// Cost per 1M input tokens, in exact micro-cents
+    pub input_micro_cents_per_1m: u64,
+    /// Cost per 1M output tokens, in exact micro-cents
+    pub output_micro_cents_per_1m: u64,
+    /// Minimum cost per request, in exact micro-cents (defaults to 0)
+    pub minimum_micro_cents: u64,
+    /// Quality tier this model qualifies for; pricing files that omit this
+    /// field default to `Standard`
+    #[serde(default)]
+    pub capability_tier: CapabilityTier,
+}
+
+impl ModelPricing {
+    /// Cost per 1M input tokens, in cents, for display/compatibility
+    pub fn input_cost_per_1m(&self) -> f64 {
+        self.input_micro_cents_per_1m as f64 / MICRO_CENTS_PER_CENT as f64
+    }
+
+    /// Cost per 1M output tokens, in cents, for display/compatibility
+    pub fn output_cost_per_1m(&self) -> f64 {
+        self.output_micro_cents_per_1m as f64 / MICRO_CENTS_PER_CENT as f64
+    }
+
+    /// Minimum cost per request, in cents, for display/compatibility
+    pub fn minimum_cost(&self) -> f64 {
+        self.minimum_micro_cents as f64 / MICRO_CENTS_PER_CENT as f64
+    }
 }
 
 /// Estimated cost for a single request
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EstimatedCost {
     /// Model used
     pub model_id: String,
@@ -32,29 +91,69 @@ pub struct EstimatedCost {
     pub input_tokens: usize,
     /// Estimated output tokens
     pub output_tokens: usize,
-    /// Total cost in cents (USD)
-    pub total_cents: f64,
-    /// Cost breakdown: (input_cost, output_cost)
-    pub breakdown: (f64, f64),
+    /// Total cost in exact micro-cents (USD)
+    pub total_micro_cents: u64,
+    /// Cost breakdown in exact micro-cents: (input_cost, output_cost)
+    pub breakdown_micro_cents: (u64, u64),
+}
+
+impl EstimatedCost {
+    /// Total cost in cents, for display/compatibility
+    pub fn total_cents(&self) -> f64 {
+        self.total_micro_cents as f64 / MICRO_CENTS_PER_CENT as f64
+    }
+
+    /// Cost breakdown in cents, for display/compatibility: (input_cost, output_cost)
+    pub fn breakdown(&self) -> (f64, f64) {
+        (
+            self.breakdown_micro_cents.0 as f64 / MICRO_CENTS_PER_CENT as f64,
+            self.breakdown_micro_cents.1 as f64 / MICRO_CENTS_PER_CENT as f64,
+        )
+    }
 }
 
 /// Comparison of costs across models
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostComparison {
     /// Primary estimate (recommended)
     pub primary: EstimatedCost,
     /// Alternative cheaper options
     pub alternatives: Vec<EstimatedCost>,
-    /// Estimated savings using cheapest option (in cents)
-    pub savings_cents: f64,
+    /// Estimated savings using cheapest option, in exact micro-cents
+    pub savings_micro_cents: u64,
     /// Percentage savings
     pub savings_percent: f64,
 }
 
+impl CostComparison {
+    /// Estimated savings using the cheapest option, in cents, for display/compatibility
+    pub fn savings_cents(&self) -> f64 {
+        self.savings_micro_cents as f64 / MICRO_CENTS_PER_CENT as f64
+    }
+}
+
+/// A pricing table loaded from a file or fetched from a remote manifest URL,
+/// layered over the built-in defaults by [`CostEstimator::from_pricing_file`]
+/// and [`CostEstimator::refresh_from_url`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingManifest {
+    /// Free-form version identifier for the pricing table, surfaced via
+    /// [`CostEstimator::pricing_version`]
+    pub pricing_version: String,
+    /// `model_id -> pricing` overrides to merge over the built-in defaults
+    pub models: HashMap<String, ModelPricing>,
+}
+
 /// Cost estimator for multiple providers
 #[derive(Clone)]
 pub struct CostEstimator {
     pricing: HashMap<String, ModelPricing>,
+    /// Identifies which pricing table is loaded; `"builtin-2024"` until a
+    /// file or remote manifest is merged in
+    pricing_version: String,
+    /// When the currently-loaded pricing table was fetched or loaded, as an
+    /// RFC 3339 timestamp; `None` for the built-in defaults
+    fetched_at: Option<String>,
 }
 
 impl CostEstimator {
@@ -68,9 +167,10 @@ impl CostEstimator {
             ModelPricing {
                 model_id: "gpt-4-turbo".to_string(),
                 provider: "openai".to_string(),
-                input_cost_per_1m: 1000.0, // $0.01 per 1K tokens
-                output_cost_per_1m: 3000.0, // $0.03 per 1K tokens
-                minimum_cost: 0.0,
+                input_micro_cents_per_1m: cents(1000), // $0.01 per 1K tokens
+                output_micro_cents_per_1m: cents(3000), // $0.03 per 1K tokens
+                minimum_micro_cents: 0,
+                capability_tier: CapabilityTier::Advanced,
             },
         );
 
@@ -79,9 +179,10 @@ impl CostEstimator {
             ModelPricing {
                 model_id: "gpt-4".to_string(),
                 provider: "openai".to_string(),
-                input_cost_per_1m: 3000.0, // $0.03 per 1K tokens
-                output_cost_per_1m: 6000.0, // $0.06 per 1K tokens
-                minimum_cost: 0.0,
+                input_micro_cents_per_1m: cents(3000), // $0.03 per 1K tokens
+                output_micro_cents_per_1m: cents(6000), // $0.06 per 1K tokens
+                minimum_micro_cents: 0,
+                capability_tier: CapabilityTier::Advanced,
             },
         );
 
@@ -90,9 +191,10 @@ impl CostEstimator {
             ModelPricing {
                 model_id: "gpt-3.5-turbo".to_string(),
                 provider: "openai".to_string(),
-                input_cost_per_1m: 50.0, // $0.0005 per 1K tokens
-                output_cost_per_1m: 150.0, // $0.0015 per 1K tokens
-                minimum_cost: 0.0,
+                input_micro_cents_per_1m: cents(50), // $0.0005 per 1K tokens
+                output_micro_cents_per_1m: cents(150), // $0.0015 per 1K tokens
+                minimum_micro_cents: 0,
+                capability_tier: CapabilityTier::Standard,
             },
         );
 
@@ -102,9 +204,10 @@ impl CostEstimator {
             ModelPricing {
                 model_id: "claude-3-opus".to_string(),
                 provider: "anthropic".to_string(),
-                input_cost_per_1m: 1500.0, // $0.015 per 1K tokens
-                output_cost_per_1m: 7500.0, // $0.075 per 1K tokens
-                minimum_cost: 0.0,
+                input_micro_cents_per_1m: cents(1500), // $0.015 per 1K tokens
+                output_micro_cents_per_1m: cents(7500), // $0.075 per 1K tokens
+                minimum_micro_cents: 0,
+                capability_tier: CapabilityTier::Advanced,
             },
         );
 
@@ -113,9 +216,10 @@ impl CostEstimator {
             ModelPricing {
                 model_id: "claude-3-sonnet".to_string(),
                 provider: "anthropic".to_string(),
-                input_cost_per_1m: 300.0, // $0.003 per 1K tokens
-                output_cost_per_1m: 1500.0, // $0.015 per 1K tokens
-                minimum_cost: 0.0,
+                input_micro_cents_per_1m: cents(300), // $0.003 per 1K tokens
+                output_micro_cents_per_1m: cents(1500), // $0.015 per 1K tokens
+                minimum_micro_cents: 0,
+                capability_tier: CapabilityTier::Standard,
             },
         );
 
@@ -124,9 +228,10 @@ impl CostEstimator {
             ModelPricing {
                 model_id: "claude-3-haiku".to_string(),
                 provider: "anthropic".to_string(),
-                input_cost_per_1m: 80.0, // $0.0008 per 1K tokens
-                output_cost_per_1m: 240.0, // $0.0024 per 1K tokens
-                minimum_cost: 0.0,
+                input_micro_cents_per_1m: cents(80), // $0.0008 per 1K tokens
+                output_micro_cents_per_1m: cents(240), // $0.0024 per 1K tokens
+                minimum_micro_cents: 0,
+                capability_tier: CapabilityTier::Basic,
             },
         );
 
@@ -136,9 +241,10 @@ impl CostEstimator {
             ModelPricing {
                 model_id: "gemini-1.5-pro".to_string(),
                 provider: "google".to_string(),
-                input_cost_per_1m: 350.0, // $0.0035 per 1K tokens
-                output_cost_per_1m: 1050.0, // $0.0105 per 1K tokens
-                minimum_cost: 0.0,
+                input_micro_cents_per_1m: cents(350), // $0.0035 per 1K tokens
+                output_micro_cents_per_1m: cents(1050), // $0.0105 per 1K tokens
+                minimum_micro_cents: 0,
+                capability_tier: CapabilityTier::Standard,
             },
         );
 
@@ -147,13 +253,64 @@ impl CostEstimator {
             ModelPricing {
                 model_id: "gemini-pro".to_string(),
                 provider: "google".to_string(),
-                input_cost_per_1m: 0.0, // Free tier available
-                output_cost_per_1m: 0.0,
-                minimum_cost: 0.0,
+                input_micro_cents_per_1m: 0, // Free tier available
+                output_micro_cents_per_1m: 0,
+                minimum_micro_cents: 0,
+                capability_tier: CapabilityTier::Basic,
             },
         );
 
-        Self { pricing }
+        Self {
+            pricing,
+            pricing_version: "builtin-2024".to_string(),
+            fetched_at: None,
+        }
+    }
+
+    /// Load a pricing manifest from a local JSON or TOML file (chosen by
+    /// extension) and merge its entries over the built-in defaults
+    pub fn from_pricing_file(path: &std::path::Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let manifest: PricingManifest = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&contents)?,
+            _ => serde_json::from_str(&contents)?,
+        };
+
+        let mut estimator = Self::new();
+        estimator.merge_pricing(manifest);
+        Ok(estimator)
+    }
+
+    /// Fetch an updated pricing manifest from `url` and merge it over the
+    /// current table. On any network or parse failure the existing table is
+    /// left untouched and the error is returned, so a failed refresh falls
+    /// back to the last-known pricing rather than losing it.
+    pub async fn refresh_from_url(&mut self, url: &str) -> AcpResult<()> {
+        let manifest: PricingManifest = reqwest::get(url).await?.json().await?;
+        self.merge_pricing(manifest);
+        Ok(())
+    }
+
+    /// Merge `manifest`'s model entries over the current table and record
+    /// its version and fetch time
+    fn merge_pricing(&mut self, manifest: PricingManifest) {
+        for (model_id, pricing) in manifest.models {
+            self.pricing.insert(model_id, pricing);
+        }
+        self.pricing_version = manifest.pricing_version;
+        self.fetched_at = Some(chrono::Utc::now().to_rfc3339());
+    }
+
+    /// Identifies which pricing table is currently loaded
+    pub fn pricing_version(&self) -> &str {
+        &self.pricing_version
+    }
+
+    /// When the currently-loaded pricing table was fetched or loaded, as an
+    /// RFC 3339 timestamp; `None` for the built-in defaults that ship with
+    /// `CostEstimator::new`
+    pub fn fetched_at(&self) -> Option<&str> {
+        self.fetched_at.as_deref()
     }
 
     /// Register custom pricing for a model
@@ -169,17 +326,27 @@ impl CostEstimator {
         output_tokens: usize,
     ) -> Option<EstimatedCost> {
         self.pricing.get(model_id).map(|pricing| {
-            let input_cost = (input_tokens as f64 / 1_000_000.0) * pricing.input_cost_per_1m;
-            let output_cost = (output_tokens as f64 / 1_000_000.0) * pricing.output_cost_per_1m;
-            let total_cents = (input_cost + output_cost).max(pricing.minimum_cost);
+            let input_micro_cents = u64::try_from(div_round(
+                input_tokens as u128 * pricing.input_micro_cents_per_1m as u128,
+                1_000_000,
+            ))
+            .unwrap_or(u64::MAX);
+            let output_micro_cents = u64::try_from(div_round(
+                output_tokens as u128 * pricing.output_micro_cents_per_1m as u128,
+                1_000_000,
+            ))
+            .unwrap_or(u64::MAX);
+            let total_micro_cents = input_micro_cents
+                .saturating_add(output_micro_cents)
+                .max(pricing.minimum_micro_cents);
 
             EstimatedCost {
                 model_id: model_id.to_string(),
                 provider: pricing.provider.clone(),
                 input_tokens,
                 output_tokens,
-                total_cents,
-                breakdown: (input_cost, output_cost),
+                total_micro_cents,
+                breakdown_micro_cents: (input_micro_cents, output_micro_cents),
             }
         })
     }
@@ -194,29 +361,102 @@ impl CostEstimator {
     ) -> Option<CostComparison> {
         let primary = self.estimate_cost(primary_model, input_tokens, output_tokens)?;
 
-        let mut alternatives: Vec<_> = alternative_providers
+        let alternatives: Vec<_> = alternative_providers
             .iter()
             .filter_map(|&model| self.estimate_cost(model, input_tokens, output_tokens))
             .collect();
 
-        alternatives.sort_by(|a, b| a.total_cents.partial_cmp(&b.total_cents).unwrap());
+        Some(Self::build_comparison(primary, alternatives))
+    }
+
+    /// Recommend the cheapest model at or above `min_tier` that fits within
+    /// `budget_cents`, compared against `primary_model` the same way
+    /// [`Self::compare_costs`] does. This lets an agent auto-downgrade to a
+    /// cheaper model only when the task's quality requirements still permit
+    /// it, rather than always racing to the free tier the way
+    /// [`Self::cheapest_model`] does. Returns `None` when `primary_model` is
+    /// unpriced.
+    pub fn recommend(
+        &self,
+        primary_model: &str,
+        input_tokens: usize,
+        output_tokens: usize,
+        min_tier: CapabilityTier,
+        budget_cents: f64,
+    ) -> Option<CostComparison> {
+        let primary = self.estimate_cost(primary_model, input_tokens, output_tokens)?;
+        let budget_micro_cents = (budget_cents * MICRO_CENTS_PER_CENT as f64).round() as u64;
+
+        let alternatives: Vec<_> = self
+            .pricing
+            .values()
+            .filter(|pricing| pricing.model_id != primary_model && pricing.capability_tier >= min_tier)
+            .filter_map(|pricing| self.estimate_cost(&pricing.model_id, input_tokens, output_tokens))
+            .filter(|cost| cost.total_micro_cents <= budget_micro_cents)
+            .collect();
 
-        let cheapest_cost = alternatives.first().map(|c| c.total_cents).unwrap_or(primary.total_cents);
-        let savings_cents = (primary.total_cents - cheapest_cost).max(0.0);
-        let savings_percent = if primary.total_cents > 0.0 {
-            (savings_cents / primary.total_cents) * 100.0
+        Some(Self::build_comparison(primary, alternatives))
+    }
+
+    /// Build a [`CostComparison`], sorting `alternatives` cheapest-first and
+    /// computing savings against `primary`. Shared by [`Self::compare_costs`]
+    /// and [`Self::recommend`] so both report savings the same way.
+    fn build_comparison(primary: EstimatedCost, mut alternatives: Vec<EstimatedCost>) -> CostComparison {
+        alternatives.sort_by_key(|c| c.total_micro_cents);
+
+        let cheapest_micro_cents = alternatives
+            .first()
+            .map(|c| c.total_micro_cents)
+            .unwrap_or(primary.total_micro_cents);
+        let savings_micro_cents = primary.total_micro_cents.saturating_sub(cheapest_micro_cents);
+        let savings_percent = if primary.total_micro_cents > 0 {
+            (savings_micro_cents as f64 / primary.total_micro_cents as f64) * 100.0
         } else {
             0.0
         };
 
-        Some(CostComparison {
+        CostComparison {
             primary,
             alternatives,
-            savings_cents,
+            savings_micro_cents,
             savings_percent,
+        }
+    }
+
+    /// Render `cost` as a single trajectory-log JSON entry: timestamp,
+    /// model, provider, token counts, and the exact micro-cent total, ready
+    /// to be appended as one line to `.vtcode/logs/trajectory.jsonl`
+    pub fn to_log_entry(cost: &EstimatedCost) -> serde_json::Value {
+        serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "model": cost.model_id,
+            "provider": cost.provider,
+            "input_tokens": cost.input_tokens,
+            "output_tokens": cost.output_tokens,
+            "total_micro_cents": cost.total_micro_cents,
         })
     }
 
+    /// Append `cost` as one JSON line to the trajectory log at `log_path`,
+    /// creating the file (and its parent directory) if it doesn't exist yet
+    pub fn append_to_trajectory_log(
+        log_path: &std::path::Path,
+        cost: &EstimatedCost,
+    ) -> std::io::Result<()> {
+        use std::io::Write;
+
+        if let Some(parent) = log_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path)?;
+
+        writeln!(file, "{}", Self::to_log_entry(cost))
+    }
+
     /// Get all models for a provider
     pub fn models_for_provider(&self, provider: &str) -> Vec<&ModelPricing> {
         self.pricing
@@ -234,7 +474,7 @@ impl CostEstimator {
         self.pricing
             .keys()
             .filter_map(|model_id| self.estimate_cost(model_id, input_tokens, output_tokens))
-            .min_by(|a, b| a.total_cents.partial_cmp(&b.total_cents).unwrap())
+            .min_by_key(|c| c.total_micro_cents)
     }
 
     /// Format cost for display
@@ -256,7 +496,7 @@ impl CostEstimator {
             "  Primary: {} - {} tokens â†’ {}\n",
             comparison.primary.model_id,
             comparison.primary.input_tokens + comparison.primary.output_tokens,
-            Self::format_cost_dollars(comparison.primary.total_cents)
+            Self::format_cost_dollars(comparison.primary.total_cents())
         ));
 
         if !comparison.alternatives.is_empty() {
@@ -266,7 +506,7 @@ impl CostEstimator {
                     "    {}. {} - {}\n",
                     i + 1,
                     alt.model_id,
-                    Self::format_cost_dollars(alt.total_cents)
+                    Self::format_cost_dollars(alt.total_cents())
                 ));
             }
 
@@ -274,7 +514,7 @@ impl CostEstimator {
                 output.push_str(&format!(
                     "\n  ðŸ’¡ Save {:.0}% ({}) by using {}\n",
                     comparison.savings_percent,
-                    Self::format_cost_dollars(comparison.savings_cents),
+                    Self::format_cost_dollars(comparison.savings_cents()),
                     comparison
                         .alternatives
                         .first()
@@ -313,7 +553,7 @@ mod tests {
         let cost = cost.unwrap();
         assert_eq!(cost.input_tokens, 1000);
         assert_eq!(cost.output_tokens, 500);
-        assert!(cost.total_cents > 0.0);
+        assert!(cost.total_cents() > 0.0);
     }
 
     #[test]
@@ -329,7 +569,56 @@ mod tests {
         assert!(comparison.is_some());
         let comparison = comparison.unwrap();
         assert!(comparison.alternatives.len() > 0);
-        assert!(comparison.savings_cents >= 0.0);
+        assert!(comparison.savings_cents() >= 0.0);
+    }
+
+    #[test]
+    fn test_recommend_skips_models_below_min_tier() {
+        let estimator = CostEstimator::new();
+        // gemini-pro and claude-3-haiku are Basic; requiring Standard+ should
+        // skip both even though gemini-pro's free tier is the cheapest overall
+        let recommendation = estimator
+            .recommend("gpt-4", 10000, 5000, CapabilityTier::Standard, 1000.0)
+            .unwrap();
+
+        assert!(
+            recommendation
+                .alternatives
+                .iter()
+                .all(|alt| alt.model_id != "gemini-pro" && alt.model_id != "claude-3-haiku")
+        );
+    }
+
+    #[test]
+    fn test_recommend_discards_options_over_budget() {
+        let estimator = CostEstimator::new();
+        // gpt-3.5-turbo is the cheapest Standard+ model for this workload at
+        // ~1.25 cents; a 1 cent budget should rule out every alternative
+        let recommendation = estimator
+            .recommend("gpt-4", 10000, 5000, CapabilityTier::Standard, 1.0)
+            .unwrap();
+
+        assert!(recommendation.alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_recommend_picks_cheapest_qualifying_model() {
+        let estimator = CostEstimator::new();
+        let recommendation = estimator
+            .recommend("gpt-4", 10000, 5000, CapabilityTier::Standard, 1000.0)
+            .unwrap();
+
+        let cheapest = recommendation.alternatives.first().unwrap();
+        assert_eq!(cheapest.model_id, "gpt-3.5-turbo");
+        assert!(recommendation.savings_cents() > 0.0);
+    }
+
+    #[test]
+    fn test_recommend_unknown_primary_returns_none() {
+        let estimator = CostEstimator::new();
+        let recommendation =
+            estimator.recommend("unknown-model", 1000, 500, CapabilityTier::Basic, 1000.0);
+        assert!(recommendation.is_none());
     }
 
     #[test]
@@ -340,7 +629,7 @@ mod tests {
         assert!(cheapest.is_some());
         // Gemini Pro should be cheapest (free tier)
         let cheapest = cheapest.unwrap();
-        assert_eq!(cheapest.total_cents, 0.0);
+        assert_eq!(cheapest.total_cents(), 0.0);
     }
 
     #[test]
@@ -349,9 +638,10 @@ mod tests {
         estimator.register_model(ModelPricing {
             model_id: "custom-model".to_string(),
             provider: "custom".to_string(),
-            input_cost_per_1m: 100,
-            output_cost_per_1m: 200,
-            minimum_cost: 0.0,
+            input_micro_cents_per_1m: cents(100),
+            output_micro_cents_per_1m: cents(200),
+            minimum_micro_cents: 0,
+            capability_tier: CapabilityTier::Standard,
         });
 
         let cost = estimator.estimate_cost("custom-model", 1000, 500);
@@ -379,7 +669,7 @@ mod tests {
 
         assert!(cost.is_some());
         let cost = cost.unwrap();
-        assert_eq!(cost.total_cents, 0.0);
+        assert_eq!(cost.total_cents(), 0.0);
     }
 
     #[test]
@@ -389,4 +679,107 @@ mod tests {
 
         assert!(cost.is_none());
     }
+
+    #[test]
+    fn test_summing_many_tiny_calls_has_no_drift() {
+        let estimator = CostEstimator::new();
+        // claude-3-haiku: 80 cents / 1M input tokens -> 1 input token costs
+        // exactly 0.00008 cents = 80 micro-cents, summed exactly every time.
+        let per_call = estimator.estimate_cost("claude-3-haiku", 1, 0).unwrap();
+        assert_eq!(per_call.total_micro_cents, 80);
+
+        let total_micro_cents: u64 = (0..10_000).map(|_| per_call.total_micro_cents).sum();
+        assert_eq!(total_micro_cents, 10_000 * 80);
+    }
+
+    #[test]
+    fn test_to_log_entry_captures_call_fields() {
+        let estimator = CostEstimator::new();
+        let cost = estimator.estimate_cost("claude-3-haiku", 1000, 500).unwrap();
+        let entry = CostEstimator::to_log_entry(&cost);
+
+        assert_eq!(entry["model"], "claude-3-haiku");
+        assert_eq!(entry["provider"], "anthropic");
+        assert_eq!(entry["input_tokens"], 1000);
+        assert_eq!(entry["output_tokens"], 500);
+        assert_eq!(entry["total_micro_cents"], cost.total_micro_cents);
+        assert!(entry["timestamp"].as_str().is_some());
+    }
+
+    #[test]
+    fn test_append_to_trajectory_log_writes_one_json_line_per_call() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("logs").join("trajectory.jsonl");
+
+        let estimator = CostEstimator::new();
+        let first = estimator.estimate_cost("gpt-4", 1000, 500).unwrap();
+        let second = estimator.estimate_cost("claude-3-haiku", 2000, 1000).unwrap();
+
+        CostEstimator::append_to_trajectory_log(&log_path, &first).unwrap();
+        CostEstimator::append_to_trajectory_log(&log_path, &second).unwrap();
+
+        let contents = std::fs::read_to_string(&log_path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first_entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first_entry["model"], "gpt-4");
+        let second_entry: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second_entry["model"], "claude-3-haiku");
+    }
+
+    #[test]
+    fn test_from_pricing_file_merges_json_over_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pricing.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "pricing_version": "2026-07-custom",
+                "models": {
+                    "gpt-4": {
+                        "model_id": "gpt-4",
+                        "provider": "openai",
+                        "input_micro_cents_per_1m": 1000000,
+                        "output_micro_cents_per_1m": 2000000,
+                        "minimum_micro_cents": 0
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let estimator = CostEstimator::from_pricing_file(&path).unwrap();
+        assert_eq!(estimator.pricing_version(), "2026-07-custom");
+        assert!(estimator.fetched_at().is_some());
+
+        // Overridden model reflects the file, untouched models keep the defaults
+        let overridden = estimator.estimate_cost("gpt-4", 1_000_000, 0).unwrap();
+        assert_eq!(overridden.total_micro_cents, 1_000_000);
+        assert!(estimator.estimate_cost("claude-3-haiku", 1, 0).is_some());
+    }
+
+    #[test]
+    fn test_from_pricing_file_supports_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pricing.toml");
+        std::fs::write(
+            &path,
+            r#"
+pricing_version = "2026-07-toml"
+
+[models.custom-model]
+model_id = "custom-model"
+provider = "custom"
+input_micro_cents_per_1m = 500000
+output_micro_cents_per_1m = 500000
+minimum_micro_cents = 0
+"#,
+        )
+        .unwrap();
+
+        let estimator = CostEstimator::from_pricing_file(&path).unwrap();
+        assert_eq!(estimator.pricing_version(), "2026-07-toml");
+        assert!(estimator.estimate_cost("custom-model", 1_000_000, 0).is_some());
+    }
 }
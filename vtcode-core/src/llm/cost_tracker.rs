@@ -0,0 +1,206 @@
+/// Session-wide cost budget tracking, adjacent to `CostEstimator`
+///
+/// `CostEstimator` only prices a single call; `CostTracker` accumulates
+/// actual spend across a whole agent session and enforces a ceiling so a
+/// runaway loop of calls fails fast instead of silently overrunning a
+/// user's budget. Like `CostEstimator`, spend is accumulated in exact
+/// integer micro-cents so summing many calls can't drift; the public API
+/// still speaks in cents.
+use std::collections::HashMap;
+
+use vtcode_acp_client::error::{AcpError, AcpResult};
+
+use super::cost_estimator::{CostEstimator, EstimatedCost, MICRO_CENTS_PER_CENT};
+
+/// Accumulates spend across a session and enforces an optional budget ceiling
+#[derive(Debug, Clone)]
+pub struct CostTracker {
+    /// Budget ceiling in exact micro-cents, or `None` for unlimited
+    budget_micro_cents: Option<u64>,
+    /// Total spend recorded so far, in exact micro-cents
+    spent_micro_cents: u64,
+    /// Spend recorded so far, in exact micro-cents, keyed by provider
+    breakdown_by_provider: HashMap<String, u64>,
+}
+
+impl CostTracker {
+    /// Create a tracker with a hard budget ceiling, in cents
+    pub fn with_budget(budget_cents: f64) -> Self {
+        Self {
+            budget_micro_cents: Some(cents_to_micro_cents(budget_cents)),
+            spent_micro_cents: 0,
+            breakdown_by_provider: HashMap::new(),
+        }
+    }
+
+    /// Create a tracker with no budget ceiling; `check_call` always succeeds
+    pub fn unlimited() -> Self {
+        Self {
+            budget_micro_cents: None,
+            spent_micro_cents: 0,
+            breakdown_by_provider: HashMap::new(),
+        }
+    }
+
+    /// Project the cost of `model_id` via `estimator` and reject the call if
+    /// committing it would exceed the budget. Callers invoke this before
+    /// issuing a call, and [`Self::record_spend`] after it completes.
+    pub fn check_call(
+        &self,
+        estimator: &CostEstimator,
+        model_id: &str,
+        input_tokens: usize,
+        output_tokens: usize,
+    ) -> AcpResult<EstimatedCost> {
+        let projected = estimator
+            .estimate_cost(model_id, input_tokens, output_tokens)
+            .ok_or_else(|| AcpError::ConfigError(format!("unknown model: {model_id}")))?;
+
+        if let Some(budget_micro_cents) = self.budget_micro_cents {
+            let projected_total = self
+                .spent_micro_cents
+                .saturating_add(projected.total_micro_cents);
+            if projected_total > budget_micro_cents {
+                return Err(AcpError::BudgetExceeded {
+                    spent: self.spent_cents(),
+                    budget: budget_micro_cents as f64 / MICRO_CENTS_PER_CENT as f64,
+                    attempted: projected.total_cents(),
+                });
+            }
+        }
+
+        Ok(projected)
+    }
+
+    /// Commit the real cost of a completed call to the running total
+    pub fn record_spend(&mut self, cost: &EstimatedCost) {
+        self.spent_micro_cents += cost.total_micro_cents;
+        *self
+            .breakdown_by_provider
+            .entry(cost.provider.clone())
+            .or_insert(0) += cost.total_micro_cents;
+    }
+
+    /// Cents remaining before the budget is hit, or `f64::INFINITY` when unlimited
+    pub fn remaining_cents(&self) -> f64 {
+        match self.budget_micro_cents {
+            Some(budget) => {
+                budget.saturating_sub(self.spent_micro_cents) as f64 / MICRO_CENTS_PER_CENT as f64
+            }
+            None => f64::INFINITY,
+        }
+    }
+
+    /// Total spend recorded so far, in cents
+    pub fn spent_cents(&self) -> f64 {
+        self.spent_micro_cents as f64 / MICRO_CENTS_PER_CENT as f64
+    }
+
+    /// Reset accumulated spend and the per-provider breakdown, keeping the budget
+    pub fn reset(&mut self) {
+        self.spent_micro_cents = 0;
+        self.breakdown_by_provider.clear();
+    }
+
+    /// Spend recorded so far, in cents, grouped by provider
+    pub fn breakdown_by_provider(&self) -> HashMap<String, f64> {
+        self.breakdown_by_provider
+            .iter()
+            .map(|(provider, micro_cents)| {
+                (
+                    provider.clone(),
+                    *micro_cents as f64 / MICRO_CENTS_PER_CENT as f64,
+                )
+            })
+            .collect()
+    }
+}
+
+fn cents_to_micro_cents(cents: f64) -> u64 {
+    (cents * MICRO_CENTS_PER_CENT as f64).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(provider: &str, total_cents: f64) -> EstimatedCost {
+        let total_micro_cents = cents_to_micro_cents(total_cents);
+        EstimatedCost {
+            model_id: "test-model".to_string(),
+            provider: provider.to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            total_micro_cents,
+            breakdown_micro_cents: (total_micro_cents, 0),
+        }
+    }
+
+    #[test]
+    fn check_call_allows_spend_within_budget() {
+        let estimator = CostEstimator::new();
+        let tracker = CostTracker::with_budget(1000.0);
+        let result = tracker.check_call(&estimator, "gpt-3.5-turbo", 1000, 500);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn check_call_rejects_spend_over_budget() {
+        let estimator = CostEstimator::new();
+        let mut tracker = CostTracker::with_budget(1.0);
+        tracker.record_spend(&cost("openai", 0.9));
+
+        let result = tracker.check_call(&estimator, "gpt-4", 1_000_000, 1_000_000);
+        assert!(matches!(result, Err(AcpError::BudgetExceeded { .. })));
+    }
+
+    #[test]
+    fn unlimited_tracker_never_rejects() {
+        let estimator = CostEstimator::new();
+        let tracker = CostTracker::unlimited();
+        let result = tracker.check_call(&estimator, "gpt-4", usize::MAX / 2, usize::MAX / 2);
+        assert!(result.is_ok());
+        assert_eq!(tracker.remaining_cents(), f64::INFINITY);
+    }
+
+    #[test]
+    fn record_spend_accumulates_and_breaks_down_by_provider() {
+        let mut tracker = CostTracker::with_budget(1000.0);
+        tracker.record_spend(&cost("openai", 10.0));
+        tracker.record_spend(&cost("anthropic", 5.0));
+        tracker.record_spend(&cost("openai", 2.5));
+
+        assert_eq!(tracker.spent_cents(), 17.5);
+        assert_eq!(tracker.breakdown_by_provider().get("openai"), Some(&12.5));
+        assert_eq!(tracker.breakdown_by_provider().get("anthropic"), Some(&5.0));
+        assert_eq!(tracker.remaining_cents(), 982.5);
+    }
+
+    #[test]
+    fn reset_clears_spend_but_keeps_budget() {
+        let mut tracker = CostTracker::with_budget(100.0);
+        tracker.record_spend(&cost("openai", 50.0));
+        tracker.reset();
+
+        assert_eq!(tracker.spent_cents(), 0.0);
+        assert!(tracker.breakdown_by_provider().is_empty());
+        assert_eq!(tracker.remaining_cents(), 100.0);
+    }
+
+    #[test]
+    fn check_call_with_unknown_model_returns_config_error() {
+        let estimator = CostEstimator::new();
+        let tracker = CostTracker::with_budget(1000.0);
+        let result = tracker.check_call(&estimator, "unknown-model", 1000, 500);
+        assert!(matches!(result, Err(AcpError::ConfigError(_))));
+    }
+
+    #[test]
+    fn record_spend_of_many_tiny_calls_has_no_drift() {
+        let mut tracker = CostTracker::unlimited();
+        for _ in 0..10_000 {
+            tracker.record_spend(&cost("openai", 0.00008));
+        }
+        assert_eq!(tracker.spent_micro_cents, 10_000 * 80);
+    }
+}
@@ -6,11 +6,54 @@ use crate::config::constants::{
 use crate::gemini::Content;
 use crate::instructions::{InstructionBundle, InstructionScope, read_instruction_bundle};
 use crate::project_doc::read_project_doc;
+use async_trait::async_trait;
 use dirs::home_dir;
 use std::env;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::RwLock;
 use tracing::warn;
 
+/// Decouples prompt-markdown loading from the real filesystem, mirroring the
+/// `FileLoader`/`RealFileLoader` split rustc uses to separate source loading
+/// from actual file I/O. Lets embedded, in-memory, or remote prompt stores
+/// be swapped in for tests and for distributing VT Code as a single binary
+/// without a `prompts/` directory.
+#[async_trait]
+pub trait PromptSource: Send + Sync {
+    /// Load the prompt content for `logical_name` (e.g. `"system"`).
+    /// Returns `Ok(None)` if nothing is available under that name, rather
+    /// than treating a miss as an error.
+    async fn load(&self, logical_name: &str) -> io::Result<Option<String>>;
+}
+
+/// Default [`PromptSource`] reproducing today's behavior: looking for
+/// `prompts/<logical_name>.md` at a few candidate roots relative to the
+/// current working directory.
+#[derive(Debug, Clone, Default)]
+pub struct FsPromptSource;
+
+#[async_trait]
+impl PromptSource for FsPromptSource {
+    async fn load(&self, logical_name: &str) -> io::Result<Option<String>> {
+        let candidates = [
+            format!("prompts/{logical_name}.md"),
+            format!("../prompts/{logical_name}.md"),
+            format!("../../prompts/{logical_name}.md"),
+        ];
+
+        for path in &candidates {
+            if let Ok(content) = tokio::fs::read_to_string(path).await {
+                return Ok(Some(content));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
 const DEFAULT_SYSTEM_PROMPT: &str = r#"You are VT Code, a Rust coding agent.
 You understand codebases, make precise modifications, and solve technical problems.
 
@@ -41,6 +84,22 @@ You understand codebases, make precise modifications, and solve technical proble
 
 **Stop:** After task done. Never re-call model with empty tool results.
 
+<!-- section: reasoning -->
+**Reasoning:** Think through edge cases and alternate approaches before editing; verify assumptions against the actual code rather than guessing.
+<!-- /section -->
+
+<!-- section: examples -->
+**Examples:** See AGENTS.md and the project's own tests for concrete usage patterns before writing new code.
+<!-- /section -->
+
+<!-- section: debugging -->
+**Debugging:** Reproduce the failure first; add temporary logging only when it's safe to remove; bisect before rewriting.
+<!-- /section -->
+
+<!-- section: error_handling -->
+**Error handling:** Propagate errors with context; never swallow or silently ignore a failure.
+<!-- /section -->
+
 **Safety:**
 - `WORKSPACE_DIR` only; confirm before leaving it
 - Clean `/tmp/vtcode-*` files
@@ -90,7 +149,7 @@ pub fn default_system_prompt() -> &'static str {
 }
 
 /// System instruction configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct SystemPromptConfig {
     pub include_examples: bool,
     pub include_debugging_guides: bool,
@@ -111,51 +170,114 @@ impl Default for SystemPromptConfig {
     }
 }
 
-/// Read system prompt from markdown file
+/// Read system prompt from markdown file, using the default
+/// filesystem-backed [`PromptSource`]. Kept for callers that don't need a
+/// custom source; prefer [`read_system_prompt`] to plug in another one.
 pub async fn read_system_prompt_from_md() -> Result<String, std::io::Error> {
-    // Try to read from prompts/system.md relative to project root
-    let prompt_paths = [
-        "prompts/system.md",
-        "../prompts/system.md",
-        "../../prompts/system.md",
-    ];
-
-    for path in &prompt_paths {
-        if let Ok(content) = tokio::fs::read_to_string(path).await {
-            // Extract the main system prompt content (skip the markdown header)
-            if let Some(start) = content.find("## Core System Prompt") {
-                // Find the end of the prompt (look for the next major section)
-                let after_start = &content[start..];
-                if let Some(end) = after_start.find("## Specialized System Prompts") {
-                    let prompt_content = &after_start[..end].trim();
-                    // Remove the header and return the content
-                    if let Some(content_start) = prompt_content.find("```rust\nr#\"") {
-                        if let Some(content_end) = prompt_content[content_start..].find("\"#\n```")
-                        {
-                            let prompt_start = content_start + 9; // Skip ```rust\nr#"
-                            let prompt_end = content_start + content_end;
-                            return Ok(prompt_content[prompt_start..prompt_end].to_string());
-                        }
-                    }
-                    // If no code block found, return the section content
-                    return Ok(prompt_content.to_string());
+    read_system_prompt(None).await
+}
+
+/// Read the system prompt through `source` (falling back to
+/// [`FsPromptSource`] when `None`), extracting the core prompt section from
+/// the loaded markdown the same way the hardcoded-path version used to.
+pub async fn read_system_prompt(
+    source: Option<&Arc<dyn PromptSource>>,
+) -> Result<String, std::io::Error> {
+    let loaded = match source {
+        Some(source) => source.load("system").await?,
+        None => FsPromptSource.load("system").await?,
+    };
+
+    let Some(content) = loaded else {
+        return Ok(default_system_prompt().to_string());
+    };
+
+    // Extract the main system prompt content (skip the markdown header)
+    if let Some(start) = content.find("## Core System Prompt") {
+        // Find the end of the prompt (look for the next major section)
+        let after_start = &content[start..];
+        if let Some(end) = after_start.find("## Specialized System Prompts") {
+            let prompt_content = &after_start[..end].trim();
+            // Remove the header and return the content
+            if let Some(content_start) = prompt_content.find("```rust\nr#\"") {
+                if let Some(content_end) = prompt_content[content_start..].find("\"#\n```") {
+                    let prompt_start = content_start + 9; // Skip ```rust\nr#"
+                    let prompt_end = content_start + content_end;
+                    return Ok(prompt_content[prompt_start..prompt_end].to_string());
                 }
             }
-            // If no specific section found, return the entire content
-            return Ok(content);
+            // If no code block found, return the section content
+            return Ok(prompt_content.to_string());
         }
     }
+    // If no specific section found, return the entire content
+    Ok(content)
+}
 
-    // Fallback to the in-code default prompt if the markdown file cannot be read
-    Ok(default_system_prompt().to_string())
+/// Strip (or keep) a `<!-- section: NAME -->`…`<!-- /section -->`-delimited
+/// block from `text`. When `keep` is `false` the whole block, markers
+/// included, is removed; when `true` only the markers are stripped, leaving
+/// the section's content in place.
+fn apply_section(text: &str, name: &str, keep: bool) -> String {
+    let start_tag = format!("<!-- section: {name} -->");
+    let end_tag = "<!-- /section -->";
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(&start_tag) {
+        result.push_str(&rest[..start]);
+        let after_start = &rest[start + start_tag.len()..];
+        match after_start.find(end_tag) {
+            Some(end) => {
+                if keep {
+                    result.push_str(&after_start[..end]);
+                }
+                rest = &after_start[end + end_tag.len()..];
+            }
+            None => {
+                // Unterminated marker: treat the remainder as the section
+                // body rather than silently dropping it.
+                if keep {
+                    result.push_str(after_start);
+                }
+                rest = "";
+            }
+        }
+    }
+    result.push_str(rest);
+    result
 }
 
-/// Generate system instruction by loading from system.md
-pub async fn generate_system_instruction(_config: &SystemPromptConfig) -> Content {
-    match read_system_prompt_from_md().await {
-        Ok(prompt_content) => Content::system_text(prompt_content),
-        Err(_) => Content::system_text(default_system_prompt().to_string()),
+/// Apply a [`SystemPromptConfig`] to raw prompt text: strip the
+/// examples/debugging/error-handling/reasoning sections the config opts
+/// out of, and append a concrete response-length directive when
+/// `max_response_length` is set.
+fn apply_prompt_config(instruction: &str, config: &SystemPromptConfig) -> String {
+    let mut text = apply_section(instruction, "examples", config.include_examples);
+    text = apply_section(&text, "debugging", config.include_debugging_guides);
+    text = apply_section(&text, "error_handling", config.include_error_handling);
+    text = apply_section(&text, "reasoning", config.enable_thorough_reasoning);
+
+    if let Some(max_length) = config.max_response_length {
+        text.push_str(&format!(
+            "\n\n**Response length:** Keep responses under {max_length} tokens/characters.\n"
+        ));
     }
+
+    text
+}
+
+/// Generate system instruction by loading from system.md, optionally
+/// through a custom [`PromptSource`] instead of the real filesystem.
+pub async fn generate_system_instruction(
+    config: &SystemPromptConfig,
+    prompt_source: Option<Arc<dyn PromptSource>>,
+) -> Content {
+    let prompt_content = match read_system_prompt(prompt_source.as_ref()).await {
+        Ok(content) => content,
+        Err(_) => default_system_prompt().to_string(),
+    };
+    Content::system_text(apply_prompt_config(&prompt_content, config))
 }
 
 /// Read AGENTS.md file if present and extract agent guidelines
@@ -175,11 +297,43 @@ pub async fn read_agent_guidelines(project_root: &Path) -> Option<String> {
 pub async fn compose_system_instruction_text(
     project_root: &Path,
     vtcode_config: Option<&crate::config::VTCodeConfig>,
+    prompt_source: Option<Arc<dyn PromptSource>>,
+    prompt_config: &SystemPromptConfig,
 ) -> String {
-    let mut instruction = match read_system_prompt_from_md().await {
+    compose_instruction(
+        project_root,
+        vtcode_config,
+        prompt_source.as_ref(),
+        prompt_config,
+    )
+    .await
+    .text
+}
+
+/// The fully assembled system instruction plus which `AGENTS.md` segments
+/// were folded in, shared by [`compose_system_instruction_text`] (which
+/// only needs the final string) and [`render_composed_prompt_report`]
+/// (which also needs the provenance).
+struct ComposedInstruction {
+    text: String,
+    segments: Vec<InstructionSegmentProvenance>,
+    truncated: bool,
+    /// Every file that fed into `text`, for [`SystemInstructionCache`] to
+    /// stat on the next call and decide whether a recompute is needed.
+    contributing_paths: Vec<PathBuf>,
+}
+
+async fn compose_instruction(
+    project_root: &Path,
+    vtcode_config: Option<&crate::config::VTCodeConfig>,
+    prompt_source: Option<&Arc<dyn PromptSource>>,
+    prompt_config: &SystemPromptConfig,
+) -> ComposedInstruction {
+    let base = match read_system_prompt(prompt_source).await {
         Ok(content) => content,
         Err(_) => default_system_prompt().to_string(),
     };
+    let mut instruction = apply_prompt_config(&base, prompt_config);
 
     if let Some(cfg) = vtcode_config {
         instruction.push_str("\n\n## CONFIGURATION AWARENESS\n");
@@ -222,6 +376,13 @@ pub async fn compose_system_instruction_text(
     }
 
     let home_path = home_dir();
+    let mut segments = Vec::new();
+    let mut truncated = false;
+    let mut contributing_paths = if prompt_source.is_none() {
+        system_prompt_candidate_paths()
+    } else {
+        Vec::new()
+    };
 
     if let Some(bundle) = read_instruction_hierarchy(project_root, vtcode_config).await {
         let home_ref = home_path.as_deref();
@@ -247,35 +408,284 @@ pub async fn compose_system_instruction_text(
             ));
             instruction.push_str(segment.contents.trim());
             instruction.push_str("\n");
+
+            contributing_paths.push(segment.source.path.clone());
+            segments.push(InstructionSegmentProvenance {
+                path: display_path,
+                scope: scope.to_string(),
+            });
         }
 
         if bundle.truncated {
+            truncated = true;
             instruction.push_str(
                 "\n_Note: instruction content was truncated due to size limits. Review the source files for full details._",
             );
         }
     }
 
-    instruction
+    ComposedInstruction {
+        text: instruction,
+        segments,
+        truncated,
+        contributing_paths,
+    }
+}
+
+/// The fixed candidate paths [`FsPromptSource`] tries, in order, when
+/// resolving the `"system"` logical prompt name.
+fn system_prompt_candidate_paths() -> Vec<PathBuf> {
+    [
+        "prompts/system.md",
+        "../prompts/system.md",
+        "../../prompts/system.md",
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect()
+}
+
+/// One cached computation of [`compose_instruction`]: the resulting text,
+/// plus enough to tell whether it's still valid without redoing the work.
+struct CacheEntry {
+    project_root: PathBuf,
+    config_fingerprint: u64,
+    file_mtimes: Vec<(PathBuf, Option<SystemTime>)>,
+    text: String,
+}
+
+/// Memoizes [`compose_instruction`]'s output, invalidating it when the
+/// project root or prompt config changes, or when any file that fed into
+/// the last computation has a newer mtime. Only tracks the files recorded
+/// by the *previous* computation rather than rediscovering dependencies
+/// from scratch, which is enough to make repeated calls within a session
+/// cheap without chasing full incremental-build correctness.
+///
+/// Bypassed entirely when a custom [`PromptSource`] is supplied, since
+/// there's no stable filesystem path to stat in that case.
+#[derive(Default)]
+pub struct SystemInstructionCache {
+    entry: RwLock<Option<CacheEntry>>,
+}
+
+impl SystemInstructionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop the cached entry, forcing the next call to recompute.
+    pub async fn invalidate(&self) {
+        *self.entry.write().await = None;
+    }
+
+    /// Same as [`compose_system_instruction_text`], but served from cache
+    /// when `project_root`, `prompt_config`, and every previously recorded
+    /// contributing file's mtime are unchanged.
+    pub async fn compose_system_instruction_text(
+        &self,
+        project_root: &Path,
+        vtcode_config: Option<&crate::config::VTCodeConfig>,
+        prompt_source: Option<Arc<dyn PromptSource>>,
+        prompt_config: &SystemPromptConfig,
+    ) -> String {
+        if prompt_source.is_some() {
+            return compose_instruction(
+                project_root,
+                vtcode_config,
+                prompt_source.as_ref(),
+                prompt_config,
+            )
+            .await
+            .text;
+        }
+
+        let fingerprint = config_fingerprint(prompt_config, vtcode_config);
+
+        {
+            let guard = self.entry.read().await;
+            if let Some(cached) = guard.as_ref() {
+                if cached.project_root == project_root && cached.config_fingerprint == fingerprint
+                {
+                    let current_mtimes = stat_paths(&cached.file_mtimes_paths()).await;
+                    if current_mtimes == cached.file_mtimes {
+                        return cached.text.clone();
+                    }
+                }
+            }
+        }
+
+        let composed = compose_instruction(project_root, vtcode_config, None, prompt_config).await;
+        let file_mtimes = stat_paths(&composed.contributing_paths).await;
+        let text = composed.text.clone();
+
+        *self.entry.write().await = Some(CacheEntry {
+            project_root: project_root.to_path_buf(),
+            config_fingerprint: fingerprint,
+            file_mtimes,
+            text: composed.text,
+        });
+
+        text
+    }
+}
+
+impl CacheEntry {
+    fn file_mtimes_paths(&self) -> Vec<PathBuf> {
+        self.file_mtimes
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+/// Hash the subset of `prompt_config` and `vtcode_config` that
+/// [`compose_instruction`] actually reads, so unrelated config changes
+/// don't needlessly invalidate the cache.
+fn config_fingerprint(
+    prompt_config: &SystemPromptConfig,
+    vtcode_config: Option<&crate::config::VTCodeConfig>,
+) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    prompt_config.include_examples.hash(&mut hasher);
+    prompt_config.include_debugging_guides.hash(&mut hasher);
+    prompt_config.include_error_handling.hash(&mut hasher);
+    prompt_config.enable_thorough_reasoning.hash(&mut hasher);
+    prompt_config.max_response_length.hash(&mut hasher);
+
+    if let Some(cfg) = vtcode_config {
+        cfg.security.human_in_the_loop.hash(&mut hasher);
+        cfg.commands.allow_list.len().hash(&mut hasher);
+        cfg.commands.deny_list.len().hash(&mut hasher);
+        cfg.pty.enabled.hash(&mut hasher);
+        cfg.pty.default_rows.hash(&mut hasher);
+        cfg.pty.default_cols.hash(&mut hasher);
+        cfg.pty.command_timeout_seconds.hash(&mut hasher);
+        cfg.agent.instruction_max_bytes.hash(&mut hasher);
+        cfg.agent.instruction_files.hash(&mut hasher);
+    } else {
+        "none".hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+/// Stat every path in `paths`, recording `None` for any that don't exist
+/// or whose mtime can't be read, so a missing-then-created file (or vice
+/// versa) is still treated as a change.
+async fn stat_paths(paths: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut result = Vec::with_capacity(paths.len());
+    for path in paths {
+        let mtime = tokio::fs::metadata(path)
+            .await
+            .ok()
+            .and_then(|metadata| metadata.modified().ok());
+        result.push((path.clone(), mtime));
+    }
+    result
+}
+
+/// Which `AGENTS.md`/instruction file a composed segment came from and at
+/// what [`InstructionScope`], so callers can see exactly which file won a
+/// precedence conflict instead of reverse-engineering the hierarchy by hand.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct InstructionSegmentProvenance {
+    pub path: String,
+    pub scope: String,
+}
+
+/// Which prompt source a composed section came from and whether the
+/// config kept or stripped it.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PromptSectionProvenance {
+    pub name: String,
+    pub source: String,
+    pub included: bool,
+}
+
+/// Full report for `--print system-prompt`-style debugging: the fully
+/// assembled system instruction plus structured metadata about where each
+/// piece came from, mirroring rustc's `PrintRequest` family (`--print`).
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ComposedPromptReport {
+    pub instruction: String,
+    pub sections: Vec<PromptSectionProvenance>,
+    pub instruction_segments: Vec<InstructionSegmentProvenance>,
+    pub truncated: bool,
+    pub byte_size: usize,
+    pub config: SystemPromptConfig,
+}
+
+/// Assemble the system instruction the same way
+/// [`compose_system_instruction_text`] does, but also return provenance for
+/// every section and `AGENTS.md` segment that fed into it.
+pub async fn render_composed_prompt_report(
+    project_root: &Path,
+    vtcode_config: Option<&crate::config::VTCodeConfig>,
+    prompt_source: Option<Arc<dyn PromptSource>>,
+    prompt_config: &SystemPromptConfig,
+) -> ComposedPromptReport {
+    let section_source = if prompt_source.is_some() {
+        "custom PromptSource".to_string()
+    } else {
+        "default system prompt / prompts/system.md".to_string()
+    };
+
+    let composed = compose_instruction(
+        project_root,
+        vtcode_config,
+        prompt_source.as_ref(),
+        prompt_config,
+    )
+    .await;
+
+    let sections = [
+        ("examples", prompt_config.include_examples),
+        ("debugging", prompt_config.include_debugging_guides),
+        ("error_handling", prompt_config.include_error_handling),
+        ("reasoning", prompt_config.enable_thorough_reasoning),
+    ]
+    .into_iter()
+    .map(|(name, included)| PromptSectionProvenance {
+        name: name.to_string(),
+        source: section_source.clone(),
+        included,
+    })
+    .collect();
+
+    ComposedPromptReport {
+        byte_size: composed.text.len(),
+        instruction: composed.text,
+        sections,
+        instruction_segments: composed.segments,
+        truncated: composed.truncated,
+        config: prompt_config.clone(),
+    }
 }
 
 /// Generate system instruction with configuration and AGENTS.md guidelines incorporated
 pub async fn generate_system_instruction_with_config(
-    _config: &SystemPromptConfig,
+    config: &SystemPromptConfig,
     project_root: &Path,
     vtcode_config: Option<&crate::config::VTCodeConfig>,
+    prompt_source: Option<Arc<dyn PromptSource>>,
 ) -> Content {
-    let instruction = compose_system_instruction_text(project_root, vtcode_config).await;
+    let instruction =
+        compose_system_instruction_text(project_root, vtcode_config, prompt_source, config).await;
 
     Content::system_text(instruction)
 }
 
 /// Generate system instruction with AGENTS.md guidelines incorporated
 pub async fn generate_system_instruction_with_guidelines(
-    _config: &SystemPromptConfig,
+    config: &SystemPromptConfig,
     project_root: &Path,
+    prompt_source: Option<Arc<dyn PromptSource>>,
 ) -> Content {
-    let instruction = compose_system_instruction_text(project_root, None).await;
+    let instruction =
+        compose_system_instruction_text(project_root, None, prompt_source, config).await;
 
     Content::system_text(instruction)
 }
@@ -351,3 +761,60 @@ pub fn generate_lightweight_instruction() -> Content {
 pub fn generate_specialized_instruction() -> Content {
     Content::system_text(DEFAULT_SPECIALIZED_PROMPT.to_string())
 }
+
+/// CLI entry point for `vtcode --print system-prompt`, kept next to
+/// [`render_composed_prompt_report`] so the command and the report it
+/// renders stay in sync; `src/cli/print.rs` just delegates into
+/// [`handle_print_system_prompt_command`], the same shape as
+/// `src/cli/mcp.rs` delegating into `vtcode_core::mcp::cli`.
+pub mod cli {
+    use super::{SystemPromptConfig, render_composed_prompt_report};
+    use anyhow::Result;
+    use std::path::PathBuf;
+
+    /// `vtcode --print system-prompt` subcommand arguments.
+    pub struct PrintSystemPromptCommand {
+        pub project_root: PathBuf,
+        pub config: SystemPromptConfig,
+        /// Emit the machine-readable JSON report instead of the rendered
+        /// instruction text followed by a provenance summary.
+        pub json: bool,
+    }
+
+    /// Render [`render_composed_prompt_report`] for `command`'s settings
+    /// and print it to stdout.
+    pub async fn handle_print_system_prompt_command(
+        command: PrintSystemPromptCommand,
+        vtcode_config: Option<&crate::config::VTCodeConfig>,
+    ) -> Result<()> {
+        let report = render_composed_prompt_report(
+            &command.project_root,
+            vtcode_config,
+            None,
+            &command.config,
+        )
+        .await;
+
+        if command.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{}", report.instruction);
+            println!("\n---\n");
+            println!("byte_size: {}", report.byte_size);
+            println!("truncated: {}", report.truncated);
+            println!("sections:");
+            for section in &report.sections {
+                println!(
+                    "  - {} (included: {}, source: {})",
+                    section.name, section.included, section.source
+                );
+            }
+            println!("instruction_segments:");
+            for segment in &report.instruction_segments {
+                println!("  - {} ({})", segment.path, segment.scope);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,363 @@
+//! Parser and evaluator for cargo-platform-style `cfg()` expressions
+//!
+//! Used by `CommandResolver` to gate commands behind a target platform
+//! predicate, e.g. `cfg(all(unix, not(target_arch = "wasm32")))`.
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// A single `cfg()` atom: either a bare flag (`unix`) or a key/value pair
+/// (`target_os = "linux"`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgPredicate {
+    Name(String),
+    KeyPair(String, String),
+}
+
+/// Parsed `cfg()` expression AST
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgExpr {
+    Value(CfgPredicate),
+    Not(Box<CfgExpr>),
+    All(Vec<CfgExpr>),
+    Any(Vec<CfgExpr>),
+}
+
+/// Error returned when a `cfg()` expression fails to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CfgParseError(String);
+
+impl fmt::Display for CfgParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid cfg() expression: {}", self.0)
+    }
+}
+
+impl std::error::Error for CfgParseError {}
+
+/// The set of cfg flags and key/value pairs that describe a target platform
+pub struct CfgSet {
+    names: HashSet<String>,
+    pairs: HashSet<(String, String)>,
+}
+
+impl CfgSet {
+    /// Build a `CfgSet` describing the platform this binary was compiled for
+    pub fn current() -> Self {
+        let mut names = HashSet::new();
+        let mut pairs = HashSet::new();
+
+        if cfg!(unix) {
+            names.insert("unix".to_string());
+        }
+        if cfg!(windows) {
+            names.insert("windows".to_string());
+        }
+
+        pairs.insert((
+            "target_os".to_string(),
+            std::env::consts::OS.to_string(),
+        ));
+        pairs.insert((
+            "target_arch".to_string(),
+            std::env::consts::ARCH.to_string(),
+        ));
+        pairs.insert((
+            "target_family".to_string(),
+            std::env::consts::FAMILY.to_string(),
+        ));
+        pairs.insert((
+            "target_pointer_width".to_string(),
+            (std::mem::size_of::<usize>() * 8).to_string(),
+        ));
+
+        Self { names, pairs }
+    }
+
+    /// Evaluate `expr` against this set
+    pub fn eval(&self, expr: &CfgExpr) -> bool {
+        match expr {
+            CfgExpr::Value(CfgPredicate::Name(name)) => self.names.contains(name),
+            CfgExpr::Value(CfgPredicate::KeyPair(key, value)) => {
+                self.pairs.contains(&(key.clone(), value.clone()))
+            }
+            CfgExpr::Not(inner) => !self.eval(inner),
+            CfgExpr::All(exprs) => exprs.iter().all(|e| self.eval(e)),
+            CfgExpr::Any(exprs) => exprs.iter().any(|e| self.eval(e)),
+        }
+    }
+}
+
+/// Parse a `cfg(...)` expression string into a [`CfgExpr`]. The outer
+/// `cfg(...)` wrapper is optional; `all(unix, not(windows))` parses the same
+/// as `cfg(all(unix, not(windows)))`.
+pub fn parse_cfg_expr(input: &str) -> Result<CfgExpr, CfgParseError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+    };
+
+    let expr = if parser.peek() == Some(&Token::Ident("cfg".to_string())) {
+        parser.next();
+        parser.expect(Token::LParen)?;
+        let expr = parser.parse_expr()?;
+        parser.expect(Token::RParen)?;
+        expr
+    } else {
+        parser.parse_expr()?
+    };
+
+    if parser.pos != parser.tokens.len() {
+        return Err(CfgParseError(format!(
+            "unexpected trailing input after position {}",
+            parser.pos
+        )));
+    }
+
+    Ok(expr)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    LParen,
+    RParen,
+    Comma,
+    Eq,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, CfgParseError> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '=' => {
+                tokens.push(Token::Eq);
+                i += 1;
+            }
+            '"' => {
+                let start = i + 1;
+                let mut end = start;
+                while end < chars.len() && chars[end] != '"' {
+                    end += 1;
+                }
+                if end >= chars.len() {
+                    return Err(CfgParseError("unterminated string literal".to_string()));
+                }
+                tokens.push(Token::Str(chars[start..end].iter().collect()));
+                i = end + 1;
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            other => {
+                return Err(CfgParseError(format!("unexpected character '{other}'")));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let tok = self.tokens.get(self.pos);
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), CfgParseError> {
+        match self.next() {
+            Some(tok) if *tok == expected => Ok(()),
+            Some(tok) => Err(CfgParseError(format!(
+                "expected {expected:?}, found {tok:?}"
+            ))),
+            None => Err(CfgParseError(format!(
+                "expected {expected:?}, found end of input"
+            ))),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<CfgExpr, CfgParseError> {
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name.clone(),
+            Some(tok) => return Err(CfgParseError(format!("expected identifier, found {tok:?}"))),
+            None => return Err(CfgParseError("expected identifier, found end of input".to_string())),
+        };
+
+        match name.as_str() {
+            "not" => {
+                self.expect(Token::LParen)?;
+                let inner = self.parse_expr()?;
+                self.expect(Token::RParen)?;
+                Ok(CfgExpr::Not(Box::new(inner)))
+            }
+            "all" => Ok(CfgExpr::All(self.parse_expr_list()?)),
+            "any" => Ok(CfgExpr::Any(self.parse_expr_list()?)),
+            _ => {
+                if self.peek() == Some(&Token::Eq) {
+                    self.next();
+                    let value = match self.next() {
+                        Some(Token::Str(value)) => value.clone(),
+                        Some(tok) => {
+                            return Err(CfgParseError(format!(
+                                "expected string literal after '=', found {tok:?}"
+                            )));
+                        }
+                        None => {
+                            return Err(CfgParseError(
+                                "expected string literal after '=', found end of input".to_string(),
+                            ));
+                        }
+                    };
+                    Ok(CfgExpr::Value(CfgPredicate::KeyPair(name, value)))
+                } else {
+                    Ok(CfgExpr::Value(CfgPredicate::Name(name)))
+                }
+            }
+        }
+    }
+
+    fn parse_expr_list(&mut self) -> Result<Vec<CfgExpr>, CfgParseError> {
+        self.expect(Token::LParen)?;
+        let mut exprs = Vec::new();
+        if self.peek() == Some(&Token::RParen) {
+            self.next();
+            return Ok(exprs);
+        }
+        loop {
+            exprs.push(self.parse_expr()?);
+            match self.peek() {
+                Some(Token::Comma) => {
+                    self.next();
+                }
+                Some(Token::RParen) => {
+                    self.next();
+                    break;
+                }
+                Some(tok) => {
+                    return Err(CfgParseError(format!(
+                        "expected ',' or ')', found {tok:?}"
+                    )));
+                }
+                None => {
+                    return Err(CfgParseError(
+                        "expected ',' or ')', found end of input".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(exprs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bare_name() {
+        let expr = parse_cfg_expr("cfg(unix)").unwrap();
+        assert_eq!(expr, CfgExpr::Value(CfgPredicate::Name("unix".to_string())));
+    }
+
+    #[test]
+    fn parses_key_pair() {
+        let expr = parse_cfg_expr(r#"cfg(target_os = "linux")"#).unwrap();
+        assert_eq!(
+            expr,
+            CfgExpr::Value(CfgPredicate::KeyPair(
+                "target_os".to_string(),
+                "linux".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_nested_all_any_not() {
+        let expr = parse_cfg_expr(r#"cfg(all(unix, not(target_arch = "wasm32")))"#).unwrap();
+        assert_eq!(
+            expr,
+            CfgExpr::All(vec![
+                CfgExpr::Value(CfgPredicate::Name("unix".to_string())),
+                CfgExpr::Not(Box::new(CfgExpr::Value(CfgPredicate::KeyPair(
+                    "target_arch".to_string(),
+                    "wasm32".to_string()
+                )))),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_any_without_cfg_wrapper() {
+        let expr = parse_cfg_expr(r#"any(windows, target_os = "macos")"#).unwrap();
+        assert_eq!(
+            expr,
+            CfgExpr::Any(vec![
+                CfgExpr::Value(CfgPredicate::Name("windows".to_string())),
+                CfgExpr::Value(CfgPredicate::KeyPair(
+                    "target_os".to_string(),
+                    "macos".to_string()
+                )),
+            ])
+        );
+    }
+
+    #[test]
+    fn malformed_input_returns_error_not_panic() {
+        assert!(parse_cfg_expr("cfg(all(unix,").is_err());
+        assert!(parse_cfg_expr("cfg(unix").is_err());
+        assert!(parse_cfg_expr("cfg(target_os = )").is_err());
+        assert!(parse_cfg_expr("").is_err());
+        assert!(parse_cfg_expr("cfg(unix))").is_err());
+    }
+
+    #[test]
+    fn eval_combines_all_any_not() {
+        let mut names = HashSet::new();
+        names.insert("unix".to_string());
+        let mut pairs = HashSet::new();
+        pairs.insert(("target_arch".to_string(), "x86_64".to_string()));
+        let set = CfgSet { names, pairs };
+
+        let expr = parse_cfg_expr(r#"cfg(all(unix, not(target_arch = "wasm32")))"#).unwrap();
+        assert!(set.eval(&expr));
+
+        let expr = parse_cfg_expr(r#"cfg(all(unix, not(target_arch = "x86_64")))"#).unwrap();
+        assert!(!set.eval(&expr));
+
+        let expr = parse_cfg_expr(r#"cfg(any(windows, target_arch = "x86_64"))"#).unwrap();
+        assert!(set.eval(&expr));
+    }
+}
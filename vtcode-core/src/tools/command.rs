@@ -5,24 +5,132 @@ use super::types::*;
 use crate::config::constants::tools;
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
+use portable_pty::{CommandBuilder, PtySize, native_pty_system};
 use serde_json::{Value, json};
 use std::{
+    collections::HashMap,
     env,
+    io::{Read, Write},
     path::{Path, PathBuf},
-    process::Stdio,
     time::Duration,
 };
-use tokio::{process::Command, time::timeout};
+
+/// Terminal window size for a PTY-backed command, in character rows/columns.
+/// Plumbed through [`EnhancedTerminalInput::terminal_size`]; defaults to a
+/// conventional 80x24 terminal when not specified.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TerminalSize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
+impl TerminalSize {
+    fn to_pty_size(&self) -> PtySize {
+        PtySize {
+            rows: self.rows,
+            cols: self.cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        }
+    }
+}
+
+/// How long a timed-out command is given to exit cleanly after SIGTERM
+/// before it's escalated to SIGKILL.
+const TERMINATION_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Security policy governing which commands [`CommandTool`] will execute.
+/// Loaded from the crate's config, this replaces the old hardcoded
+/// substring blacklist with a configurable allow/deny list of program
+/// names, regex patterns matched against the full resolved script, a
+/// per-command environment map, and a shell-style alias table that's
+/// expanded on `command[0]` before validation.
+#[derive(Debug, Clone)]
+pub struct CommandPolicy {
+    /// Program names that are always rejected.
+    pub deny_programs: Vec<String>,
+    /// If non-empty, only these program names may run; everything else is
+    /// rejected.
+    pub allow_programs: Vec<String>,
+    /// Regex patterns checked against both `command.join(" ")` and the
+    /// resolved shell script; any match rejects the command.
+    pub deny_patterns: Vec<String>,
+    /// Environment variables set on the spawned command.
+    pub env: HashMap<String, String>,
+    /// Shell-style aliases (`alias ll='ls -la'`) expanded on `command[0]`.
+    pub aliases: HashMap<String, String>,
+}
+
+impl CommandPolicy {
+    /// The program names and patterns `CommandTool` rejected before the
+    /// policy subsystem existed, used when no policy is specified. The
+    /// patterns now match on word boundaries, apply to the joined argv as
+    /// well as the shell script, and include a fork-bomb pattern.
+    pub fn default_policy() -> Self {
+        Self {
+            deny_programs: ["rm", "rmdir", "del", "format", "fdisk", "mkfs", "dd"]
+                .iter()
+                .map(|program| program.to_string())
+                .collect(),
+            allow_programs: Vec::new(),
+            deny_patterns: vec![
+                r"rm\s+-rf\s+/".to_string(),
+                r"sudo\s+rm".to_string(),
+                r"\bformat\b".to_string(),
+                r"\bfdisk\b".to_string(),
+                r"\bmkfs\b".to_string(),
+                // A fork bomb: `:(){ :|:& };:` defines a function that forks
+                // two copies of itself in the background and recurses forever.
+                r":\s*\(\)\s*\{[^}]*:\s*\|\s*:".to_string(),
+            ],
+            env: HashMap::new(),
+            aliases: HashMap::new(),
+        }
+    }
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        Self::default_policy()
+    }
+}
 
 /// Command execution tool using standard process handling
 #[derive(Clone)]
 pub struct CommandTool {
     workspace_root: PathBuf,
+    policy: CommandPolicy,
+    deny_patterns: Vec<regex::Regex>,
 }
 
 impl CommandTool {
     pub fn new(workspace_root: PathBuf) -> Self {
-        Self { workspace_root }
+        Self::new_with_policy(workspace_root, CommandPolicy::default_policy())
+            .expect("default command policy patterns are valid regexes")
+    }
+
+    /// Construct a `CommandTool` governed by a custom [`CommandPolicy`]
+    /// instead of the hardcoded default rules.
+    pub fn new_with_policy(workspace_root: PathBuf, policy: CommandPolicy) -> Result<Self> {
+        let deny_patterns = policy
+            .deny_patterns
+            .iter()
+            .map(|pattern| {
+                regex::Regex::new(pattern)
+                    .with_context(|| format!("invalid deny pattern in command policy: {pattern}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            workspace_root,
+            policy,
+            deny_patterns,
+        })
     }
 
     async fn execute_terminal_command(
@@ -30,40 +138,147 @@ impl CommandTool {
         input: &EnhancedTerminalInput,
         invocation: CommandInvocation,
     ) -> Result<Value> {
-        let mut cmd = Command::new(&invocation.program);
-        cmd.args(&invocation.args);
+        self.execute_pty_command(input, invocation, "terminal").await
+    }
 
+    /// Run `invocation` attached to a PTY rather than plain pipes, so
+    /// interactive programs, color output, and progress bars render the way
+    /// they would in a real terminal. PTYs don't separate stdout/stderr, so
+    /// both are combined into a single `stdout` field.
+    async fn execute_pty_command(
+        &self,
+        input: &EnhancedTerminalInput,
+        invocation: CommandInvocation,
+        mode: &'static str,
+    ) -> Result<Value> {
         let work_dir = if let Some(ref working_dir) = input.working_dir {
             self.workspace_root.join(working_dir)
         } else {
             self.workspace_root.clone()
         };
 
-        cmd.current_dir(work_dir);
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
+        let size = input.terminal_size.clone().unwrap_or_default();
+        let pty_system = native_pty_system();
+        let pair = pty_system
+            .openpty(size.to_pty_size())
+            .context("failed to open PTY for command execution")?;
+
+        let mut cmd = CommandBuilder::new(&invocation.program);
+        cmd.args(&invocation.args);
+        cmd.cwd(&work_dir);
+        for (key, value) in &self.policy.env {
+            cmd.env(key, value);
+        }
+
+        let mut child = pair
+            .slave
+            .spawn_command(cmd)
+            .with_context(|| format!("failed to spawn command: {}", invocation.display))?;
+        // Drop our copy of the slave so the master reader sees EOF once the
+        // child exits, instead of hanging open forever.
+        drop(pair.slave);
+
+        if let Some(stdin_data) = input.stdin.clone() {
+            let mut writer = pair
+                .master
+                .take_writer()
+                .context("failed to open PTY writer for stdin")?;
+            tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+                writer.write_all(stdin_data.as_bytes())?;
+                // A PTY has no separate "close stdin" like a pipe does; Ctrl-D
+                // (EOT) is how a line-disciplined reader on the other end
+                // recognizes end-of-input.
+                writer.write_all(&[0x04])?;
+                writer.flush()
+            })
+            .await
+            .context("stdin writer task panicked")?
+            .context("failed to write command stdin")?;
+        }
+
+        let mut reader = pair
+            .master
+            .try_clone_reader()
+            .context("failed to open PTY reader")?;
+        let killer = child.clone_killer();
+        let pid = child.process_id();
 
         let duration = Duration::from_secs(input.timeout_secs.unwrap_or(30));
-        let output = timeout(duration, cmd.output())
+        let max_output_bytes = input.max_output_bytes;
+        let mut read_handle =
+            tokio::task::spawn_blocking(move || -> std::io::Result<(Vec<u8>, bool)> {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 8192];
+                let mut truncated = false;
+                loop {
+                    let read = reader.read(&mut chunk)?;
+                    if read == 0 {
+                        break;
+                    }
+                    match max_output_bytes {
+                        Some(limit) if buf.len() >= limit => truncated = true,
+                        Some(limit) => {
+                            let take = (limit - buf.len()).min(read);
+                            buf.extend_from_slice(&chunk[..take]);
+                            if take < read {
+                                truncated = true;
+                            }
+                        }
+                        None => buf.extend_from_slice(&chunk[..read]),
+                    }
+                }
+                Ok((buf, truncated))
+            });
+
+        let (output_bytes, truncated, timed_out, force_killed) = tokio::select! {
+            result = &mut read_handle => {
+                let (bytes, truncated) = result
+                    .context("PTY reader task panicked")?
+                    .context("failed to read PTY output")?;
+                (bytes, truncated, false, false)
+            }
+            _ = tokio::time::sleep(duration) => {
+                let force_killed = terminate_process_tree(pid, killer).await;
+                let (bytes, truncated) = read_handle
+                    .await
+                    .context("PTY reader task panicked")?
+                    .context("failed to read PTY output")?;
+                (bytes, truncated, true, force_killed)
+            }
+        };
+
+        let exit_status = tokio::task::spawn_blocking(move || child.wait())
             .await
-            .with_context(|| {
-                format!(
-                    "command '{}' timed out after {}s",
-                    invocation.display,
-                    duration.as_secs()
-                )
-            })?
-            .with_context(|| format!("failed to run command: {}", invocation.display))?;
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            .context("PTY child wait task panicked")?
+            .context("failed to wait for PTY child")?;
+
+        let mut stdout = String::from_utf8_lossy(&output_bytes).to_string();
+        if truncated {
+            stdout.push_str("\n... [output truncated: exceeded max_output_bytes]");
+        }
+
+        // `exit_code()` alone can't distinguish a crash from a normal
+        // non-zero exit: a command killed by a signal (segfault, SIGKILL,
+        // SIGTERM) reports a code of 0 here even though it never ran to
+        // completion. `signal()` surfaces the signal name when one fired.
+        let signal = exit_status.signal();
+        let termination_reason = match &signal {
+            Some(name) => format!("signaled:{name}"),
+            None => "exited".to_string(),
+        };
 
         Ok(json!({
-            "success": output.status.success(),
-            "exit_code": output.status.code().unwrap_or_default(),
+            "success": exit_status.success() && !timed_out,
+            "exit_code": exit_status.exit_code(),
+            "signal": signal,
+            "termination_reason": termination_reason,
             "stdout": stdout,
-            "stderr": stderr,
-            "mode": "terminal",
-            "pty_enabled": false,
+            "truncated": truncated,
+            "stderr": "",
+            "mode": mode,
+            "pty_enabled": true,
+            "timed_out": timed_out,
+            "force_killed": force_killed,
             "command": invocation.display,
             "used_shell": invocation.used_shell
         }))
@@ -74,68 +289,104 @@ impl CommandTool {
             return Err(anyhow!("Command cannot be empty"));
         }
 
-        self.validate_command_segments(&input.command)?;
+        let command = self.expand_alias(&input.command);
 
-        if let Some(invocation) = detect_explicit_shell(&input.command, &input.raw_command) {
+        self.validate_command_segments(&command)?;
+
+        if let Some(invocation) = detect_explicit_shell(&command, &input.raw_command) {
             self.validate_script(&invocation.display)?;
             return Ok(invocation);
         }
 
         let script = if let Some(raw) = &input.raw_command {
+            // Parsing (and discarding) raw's argv validates that its quoting
+            // and escaping are well-formed POSIX shell syntax before it's
+            // handed to the shell verbatim, instead of failing opaquely
+            // inside the spawned subshell.
+            shell_words::split(raw)
+                .map_err(|err| anyhow!("invalid raw_command syntax: {}", err))?;
             raw.clone()
         } else {
-            join_command_for_shell(&input.command)
+            join_command_for_shell(&command)
         };
 
         self.validate_script(&script)?;
 
-        let shell = input
+        let shell_spec = input
             .shell
             .clone()
             .filter(|value| !value.trim().is_empty())
             .unwrap_or_else(default_shell);
+        let mut shell_tokens = shell_words::split(&shell_spec)
+            .map_err(|err| anyhow!("invalid shell specification '{}': {}", shell_spec, err))?;
+        if shell_tokens.is_empty() {
+            return Err(anyhow!("Shell specification cannot be empty"));
+        }
+        let shell_program = shell_tokens.remove(0);
+        let shell_base_args = shell_tokens;
+
         let login = input.login.unwrap_or(true);
-        let args = build_shell_arguments(&shell, login, &script);
+        let args = build_shell_arguments(&shell_program, &shell_base_args, login, &script);
 
         Ok(CommandInvocation {
-            program: shell,
+            program: shell_program,
             args,
             display: script,
             used_shell: true,
         })
     }
 
+    /// Expand `command[0]` against the policy's alias table (as in a shell's
+    /// `alias` config), splicing the alias's expansion in place of the
+    /// original program name and leaving the rest of `command` untouched.
+    fn expand_alias(&self, command: &[String]) -> Vec<String> {
+        let Some(expansion) = command.first().and_then(|first| self.policy.aliases.get(first))
+        else {
+            return command.to_vec();
+        };
+
+        let mut expanded =
+            shell_words::split(expansion).unwrap_or_else(|_| vec![expansion.clone()]);
+        expanded.extend_from_slice(&command[1..]);
+        expanded
+    }
+
     fn validate_command_segments(&self, command: &[String]) -> Result<()> {
         let program = &command[0];
         if program.chars().any(char::is_whitespace) {
-            return Ok(());
+            return Err(anyhow!(
+                "Command program name contains whitespace, refusing to bypass allow/deny policy: {}",
+                program
+            ));
         }
 
-        let dangerous_commands = ["rm", "rmdir", "del", "format", "fdisk", "mkfs", "dd"];
-        if dangerous_commands.contains(&program.as_str()) {
-            return Err(anyhow!("Dangerous command not allowed: {}", program));
+        if !self.policy.allow_programs.is_empty()
+            && !self.policy.allow_programs.iter().any(|allowed| allowed == program)
+        {
+            return Err(anyhow!("Command not in allow-list: {}", program));
         }
 
-        let full_command = command.join(" ");
-        if full_command.contains("rm -rf /") || full_command.contains("sudo rm") {
-            return Err(anyhow!("Potentially dangerous command pattern detected"));
+        if self.policy.deny_programs.iter().any(|denied| denied == program) {
+            return Err(anyhow!("Dangerous command not allowed: {}", program));
         }
 
-        Ok(())
+        self.check_deny_patterns(&command.join(" "))
     }
 
     fn validate_script(&self, script: &str) -> Result<()> {
-        if script.contains("rm -rf /")
-            || script.contains("sudo rm")
-            || script.contains("format")
-            || script.contains("fdisk")
-            || script.contains("mkfs")
-        {
-            return Err(anyhow!(
-                "Potentially dangerous command pattern detected in shell command"
-            ));
-        }
+        self.check_deny_patterns(script)
+            .context("Potentially dangerous command pattern detected in shell command")
+    }
 
+    fn check_deny_patterns(&self, text: &str) -> Result<()> {
+        for pattern in &self.deny_patterns {
+            if pattern.is_match(text) {
+                return Err(anyhow!(
+                    "Command matches denied pattern '{}'",
+                    pattern.as_str()
+                ));
+            }
+        }
         Ok(())
     }
 }
@@ -165,7 +416,7 @@ impl Tool for CommandTool {
 #[async_trait]
 impl ModeTool for CommandTool {
     fn supported_modes(&self) -> Vec<&'static str> {
-        vec!["terminal"]
+        vec!["terminal", "pty"]
     }
 
     async fn execute_mode(&self, mode: &str, args: Value) -> Result<Value> {
@@ -173,6 +424,7 @@ impl ModeTool for CommandTool {
         let invocation = self.prepare_invocation(&input)?;
         match mode {
             "terminal" => self.execute_terminal_command(&input, invocation).await,
+            "pty" => self.execute_pty_command(&input, invocation, "pty").await,
             _ => Err(anyhow!("Unsupported command execution mode: {}", mode)),
         }
     }
@@ -213,36 +465,15 @@ fn detect_explicit_shell(
     })
 }
 
+/// Join `command` into a single shell script using proper POSIX shell-word
+/// quoting (the `shell-words` crate's escaping rules), so the result
+/// round-trips back to the original arguments when re-split by a shell.
 fn join_command_for_shell(command: &[String]) -> String {
-    command
-        .iter()
-        .map(|part| quote_argument(part))
-        .collect::<Vec<_>>()
-        .join(" ")
+    shell_words::join(command)
 }
 
 fn quote_argument(arg: &str) -> String {
-    if arg.is_empty() {
-        return "''".to_string();
-    }
-
-    if arg
-        .chars()
-        .all(|ch| ch.is_ascii_alphanumeric() || "-_./:@".contains(ch))
-    {
-        return arg.to_string();
-    }
-
-    let mut quoted = String::from("'");
-    for ch in arg.chars() {
-        if ch == '\'' {
-            quoted.push_str("'\"'\"'");
-        } else {
-            quoted.push(ch);
-        }
-    }
-    quoted.push('\'');
-    quoted
+    shell_words::quote(arg).into_owned()
 }
 
 fn extract_shell_script(program: &str, args: &[String]) -> Option<String> {
@@ -277,24 +508,88 @@ fn extract_shell_script(program: &str, args: &[String]) -> Option<String> {
     }
 }
 
-fn build_shell_arguments(shell: &str, login: bool, script: &str) -> Vec<String> {
-    let name = shell_program_name(shell);
+/// Build the full argument list for invoking `shell_program`, merging any
+/// user-specified base arguments (e.g. `--norc -i` split out of a `shell`
+/// spec like `"bash --norc -i"`) with the `-c`/`-lc`-style invocation of
+/// `script`.
+fn build_shell_arguments(
+    shell_program: &str,
+    shell_base_args: &[String],
+    login: bool,
+    script: &str,
+) -> Vec<String> {
+    let name = shell_program_name(shell_program);
+    let mut args = shell_base_args.to_vec();
+
     match name.as_str() {
-        "cmd" | "cmd.exe" => vec!["/C".to_string(), script.to_string()],
+        "cmd" | "cmd.exe" => {
+            args.push("/C".to_string());
+            args.push(script.to_string());
+        }
         "pwsh" | "powershell" | "powershell.exe" => {
-            let mut args = Vec::new();
             if login {
                 args.push("-NoProfile".to_string());
             }
             args.push("-Command".to_string());
             args.push(script.to_string());
-            args
         }
         _ => {
             let flag = if login { "-lc" } else { "-c" };
-            vec![flag.to_string(), script.to_string()]
+            args.push(flag.to_string());
+            args.push(script.to_string());
         }
     }
+
+    args
+}
+
+/// Terminate a timed-out command gracefully: SIGTERM the process group
+/// (on Unix, the PTY slave already put the child in its own session/process
+/// group, so a negative pid reaches it and any descendants that haven't
+/// broken away into a session of their own), wait up to
+/// [`TERMINATION_GRACE_PERIOD`] for it to exit, then SIGKILL if it's still
+/// alive. Returns whether SIGKILL was needed.
+#[cfg(unix)]
+async fn terminate_process_tree(
+    pid: Option<u32>,
+    mut killer: Box<dyn portable_pty::ChildKiller + Send + Sync>,
+) -> bool {
+    let Some(pid) = pid else {
+        let _ = killer.kill();
+        return false;
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let pgid = pid as libc::pid_t;
+        unsafe {
+            libc::kill(-pgid, libc::SIGTERM);
+        }
+
+        let deadline = std::time::Instant::now() + TERMINATION_GRACE_PERIOD;
+        while std::time::Instant::now() < deadline {
+            let alive = unsafe { libc::kill(-pgid, 0) } == 0;
+            if !alive {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        unsafe {
+            libc::kill(-pgid, libc::SIGKILL);
+        }
+        true
+    })
+    .await
+    .unwrap_or(true)
+}
+
+#[cfg(not(unix))]
+async fn terminate_process_tree(
+    _pid: Option<u32>,
+    mut killer: Box<dyn portable_pty::ChildKiller + Send + Sync>,
+) -> bool {
+    let _ = killer.kill();
+    false
 }
 
 fn is_shell_program(program: &str) -> bool {
@@ -353,7 +648,7 @@ mod tests {
     fn quotes_arguments_for_shell() {
         assert_eq!(quote_argument("simple"), "simple");
         assert_eq!(quote_argument("needs space"), "'needs space'");
-        assert_eq!(quote_argument("quote'inner"), "'quote'\"'\"'inner'");
+        assert_eq!(quote_argument("quote'inner"), "'quote'\\''inner'");
     }
 
     #[test]
@@ -362,6 +657,18 @@ mod tests {
         assert_eq!(join_command_for_shell(&parts), "echo 'hello world'");
     }
 
+    #[test]
+    fn joins_command_round_trips_through_shell_words_split() {
+        let parts = vec![
+            "echo".to_string(),
+            "hello world".to_string(),
+            "quote'inner".to_string(),
+        ];
+        let joined = join_command_for_shell(&parts);
+        let round_tripped = shell_words::split(&joined).expect("valid shell syntax");
+        assert_eq!(round_tripped, parts);
+    }
+
     #[test]
     fn detects_explicit_bash_script() {
         let args = vec!["bash".to_string(), "-lc".to_string(), "ls".to_string()];
@@ -383,10 +690,133 @@ mod tests {
             raw_command: None,
             shell: Some("/bin/bash".into()),
             login: Some(true),
+            terminal_size: None,
+            stdin: None,
+            max_output_bytes: None,
         };
         let invocation = tool.prepare_invocation(&input).expect("invocation");
         assert_eq!(invocation.program, "/bin/bash");
         assert_eq!(invocation.args[0], "-lc");
         assert_eq!(invocation.display, "cargo test");
     }
+
+    fn base_input(command: Vec<String>) -> EnhancedTerminalInput {
+        EnhancedTerminalInput {
+            command,
+            working_dir: None,
+            timeout_secs: None,
+            mode: None,
+            response_format: None,
+            raw_command: None,
+            shell: None,
+            login: Some(true),
+            terminal_size: None,
+            stdin: None,
+            max_output_bytes: None,
+        }
+    }
+
+    #[test]
+    fn prepare_invocation_accepts_raw_command_with_quoted_arguments_and_spaces() {
+        let tool = make_tool();
+        let mut input = base_input(vec!["placeholder".into()]);
+        input.raw_command = Some("echo 'hello world' foo".to_string());
+
+        let invocation = tool.prepare_invocation(&input).expect("invocation");
+        assert_eq!(invocation.display, "echo 'hello world' foo");
+        assert!(invocation.used_shell);
+    }
+
+    #[test]
+    fn prepare_invocation_rejects_malformed_raw_command_syntax() {
+        let tool = make_tool();
+        let mut input = base_input(vec!["placeholder".into()]);
+        input.raw_command = Some("echo 'unterminated".to_string());
+
+        let result = tool.prepare_invocation(&input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn prepare_invocation_splits_multi_word_shell_spec() {
+        let tool = make_tool();
+        let mut input = base_input(vec!["cargo".into(), "test".into()]);
+        input.shell = Some("bash --norc -i".to_string());
+
+        let invocation = tool.prepare_invocation(&input).expect("invocation");
+        assert_eq!(invocation.program, "bash");
+        assert_eq!(
+            invocation.args,
+            vec![
+                "--norc".to_string(),
+                "-i".to_string(),
+                "-lc".to_string(),
+                "cargo test".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn prepare_invocation_rejects_malformed_shell_spec() {
+        let tool = make_tool();
+        let mut input = base_input(vec!["cargo".into(), "test".into()]);
+        input.shell = Some("bash --opt 'unterminated".to_string());
+
+        let result = tool.prepare_invocation(&input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn default_policy_blocks_known_dangerous_commands() {
+        let tool = make_tool();
+        let input = base_input(vec!["rm".into(), "-rf".into(), "/".into()]);
+
+        let result = tool.prepare_invocation(&input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn default_policy_blocks_fork_bomb_pattern() {
+        let tool = make_tool();
+        let mut input = base_input(vec!["placeholder".into()]);
+        input.raw_command = Some(":(){ :|:& };:".to_string());
+
+        let result = tool.prepare_invocation(&input);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn custom_policy_expands_aliases() {
+        let mut policy = CommandPolicy::default_policy();
+        policy
+            .aliases
+            .insert("ll".to_string(), "ls -la".to_string());
+        let tool = CommandTool::new_with_policy(PathBuf::from("."), policy).expect("tool");
+
+        let input = base_input(vec!["ll".into(), "/tmp".into()]);
+        let invocation = tool.prepare_invocation(&input).expect("invocation");
+        assert_eq!(invocation.display, "ls -la /tmp");
+    }
+
+    #[test]
+    fn custom_policy_enforces_allow_list() {
+        let mut policy = CommandPolicy::default_policy();
+        policy.allow_programs = vec!["cargo".to_string()];
+        let tool = CommandTool::new_with_policy(PathBuf::from("."), policy).expect("tool");
+
+        let blocked = tool.prepare_invocation(&base_input(vec!["ls".into()]));
+        assert!(blocked.is_err());
+
+        let allowed = tool.prepare_invocation(&base_input(vec!["cargo".into(), "test".into()]));
+        assert!(allowed.is_ok());
+    }
+
+    #[test]
+    fn new_with_policy_rejects_invalid_regex() {
+        let mut policy = CommandPolicy::default_policy();
+        policy.deny_patterns.push("(unterminated".to_string());
+
+        let result = CommandTool::new_with_policy(PathBuf::from("."), policy);
+        assert!(result.is_err());
+    }
 }
@@ -2,10 +2,15 @@
 //! Maps command names to their actual filesystem paths
 //! Used by policy evaluator to validate and log command locations
 
+use anyhow::{Result, anyhow};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use tracing::{debug, warn};
 
+#[path = "cfg_expr.rs"]
+mod cfg_expr;
+pub use cfg_expr::{CfgExpr, CfgParseError, CfgPredicate, CfgSet, parse_cfg_expr};
+
 /// Result of attempting to resolve a command to a filesystem path
 #[derive(Debug, Clone)]
 pub struct CommandResolution {
@@ -20,6 +25,16 @@ pub struct CommandResolution {
 
     /// Environment used for resolution
     pub search_paths: Vec<PathBuf>,
+
+    /// Set when `found` is false because a platform gate rejected the
+    /// command rather than because it was missing from PATH
+    pub reason: Option<String>,
+
+    /// Every PATH directory containing an executable named `command`, in
+    /// PATH search order. `resolved_path` is always `all_matches.first()`;
+    /// additional entries shadow it and are surfaced so callers can warn
+    /// users about e.g. a project-local `cargo` ahead of the rustup shim.
+    pub all_matches: Vec<PathBuf>,
 }
 
 /// Resolver with built-in caching to avoid repeated PATH searches
@@ -30,8 +45,12 @@ pub struct CommandResolver {
     /// Cache hit count for metrics
     cache_hits: usize,
 
-    /// Cache miss count for metrics  
+    /// Cache miss count for metrics
     cache_misses: usize,
+
+    /// Per-command `cfg()` predicates gating whether the command is allowed
+    /// on the current platform
+    platform_gates: HashMap<String, CfgExpr>,
 }
 
 impl CommandResolver {
@@ -41,9 +60,26 @@ impl CommandResolver {
             cache: HashMap::new(),
             cache_hits: 0,
             cache_misses: 0,
+            platform_gates: HashMap::new(),
         }
     }
 
+    /// Gate `cmd` behind a `cfg()` predicate, e.g.
+    /// `cfg(all(unix, not(target_arch = "wasm32")))`. Returns an error if the
+    /// expression fails to parse. Setting a gate clears any cached
+    /// resolution for `cmd` so the gate takes effect immediately.
+    pub fn set_platform_gate(&mut self, cmd: &str, expr: &str) -> Result<()> {
+        let parsed = parse_cfg_expr(expr).map_err(|err| anyhow!(err))?;
+        self.platform_gates.insert(cmd.to_string(), parsed);
+        self.cache.remove(cmd);
+        Ok(())
+    }
+
+    /// Remove any platform gate previously set for `cmd`
+    pub fn clear_platform_gate(&mut self, cmd: &str) {
+        self.platform_gates.remove(cmd);
+    }
+
     /// Resolve a command to its filesystem path
     ///
     /// # Example
@@ -71,13 +107,53 @@ impl CommandResolver {
 
         self.cache_misses += 1;
 
-        // Try to find command in system PATH
-        let resolution = if let Ok(path) = which::which(base_cmd) {
+        if let Some(gate) = self.platform_gates.get(base_cmd) {
+            if !CfgSet::current().eval(gate) {
+                let reason = format!(
+                    "command '{base_cmd}' is gated by a cfg() predicate that does not match the current platform"
+                );
+                warn!(command = base_cmd, reason = %reason, "Command rejected by platform gate");
+                let resolution = CommandResolution {
+                    command: base_cmd.to_string(),
+                    resolved_path: None,
+                    found: false,
+                    search_paths: Self::get_search_paths(),
+                    reason: Some(reason),
+                    all_matches: Vec::new(),
+                };
+                self.cache.insert(base_cmd.to_string(), resolution.clone());
+                return resolution;
+            }
+        }
+
+        // Scan every PATH directory so we can report shadowed binaries, not
+        // just the first hit
+        let mut all_matches = Self::scan_path_for_matches(base_cmd);
+        if all_matches.is_empty() {
+            // which::which understands platform-specific lookup quirks
+            // (e.g. Windows PATHEXT) our manual scan doesn't; fall back to it
+            if let Ok(path) = which::which(base_cmd) {
+                all_matches.push(path);
+            }
+        }
+
+        if all_matches.len() > 1 {
+            warn!(
+                command = base_cmd,
+                matches = all_matches.len(),
+                shadowed = ?&all_matches[1..],
+                "Multiple PATH entries resolve command; earlier entry shadows the rest"
+            );
+        }
+
+        let resolution = if let Some(first) = all_matches.first().cloned() {
             CommandResolution {
                 command: base_cmd.to_string(),
-                resolved_path: Some(path.clone()),
+                resolved_path: Some(first),
                 found: true,
                 search_paths: Self::get_search_paths(),
+                reason: None,
+                all_matches,
             }
         } else {
             warn!(command = base_cmd, "Command not found in PATH");
@@ -86,6 +162,8 @@ impl CommandResolver {
                 resolved_path: None,
                 found: false,
                 search_paths: Self::get_search_paths(),
+                reason: None,
+                all_matches,
             }
         };
 
@@ -94,6 +172,14 @@ impl CommandResolver {
         resolution
     }
 
+    /// Resolve `cmd` and return every PATH match for it in PATH search
+    /// order: the one that wins first, then the ones it shadows. Useful for
+    /// the policy/logging layer to surface "an
+    /// earlier directory shadows the binary you expect" to users.
+    pub fn resolve_all(&mut self, cmd: &str) -> Vec<PathBuf> {
+        self.resolve(cmd).all_matches
+    }
+
     /// Get current PATH directories being searched
     fn get_search_paths() -> Vec<PathBuf> {
         std::env::var_os("PATH")
@@ -101,6 +187,29 @@ impl CommandResolver {
             .unwrap_or_default()
     }
 
+    /// Scan every PATH directory for an executable named `cmd`, in PATH
+    /// search order, unlike `which::which` which stops at the first hit
+    fn scan_path_for_matches(cmd: &str) -> Vec<PathBuf> {
+        Self::get_search_paths()
+            .into_iter()
+            .map(|dir| dir.join(cmd))
+            .filter(|candidate| Self::is_executable_file(candidate))
+            .collect()
+    }
+
+    #[cfg(unix)]
+    fn is_executable_file(path: &std::path::Path) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path)
+            .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(unix))]
+    fn is_executable_file(path: &std::path::Path) -> bool {
+        path.is_file()
+    }
+
     /// Clear the resolution cache
     pub fn clear_cache(&mut self) {
         self.cache.clear();
@@ -123,8 +232,18 @@ impl Default for CommandResolver {
 mod tests {
     use super::*;
 
+    /// Serializes every test in this module against the one that mutates
+    /// the process-wide `PATH` env var
+    /// (`test_resolve_all_reports_shadowed_duplicates`): cargo's default
+    /// test harness runs tests in this file concurrently, and the other
+    /// tests here read the real system `PATH` through [`CommandResolver`],
+    /// so they'd intermittently observe the temporarily-overridden value
+    /// without this.
+    static PATH_ENV_MUTEX: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
     #[test]
     fn test_resolve_common_command() {
+        let _guard = PATH_ENV_MUTEX.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
         let mut resolver = CommandResolver::new();
         let ls = resolver.resolve("ls");
         assert_eq!(ls.command, "ls");
@@ -134,6 +253,7 @@ mod tests {
 
     #[test]
     fn test_cache_hits() {
+        let _guard = PATH_ENV_MUTEX.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
         let mut resolver = CommandResolver::new();
         resolver.resolve("ls");
         resolver.resolve("ls");
@@ -144,6 +264,7 @@ mod tests {
 
     #[test]
     fn test_nonexistent_command() {
+        let _guard = PATH_ENV_MUTEX.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
         let mut resolver = CommandResolver::new();
         let fake = resolver.resolve("this_command_definitely_does_not_exist_xyz");
         assert_eq!(fake.command, "this_command_definitely_does_not_exist_xyz");
@@ -152,9 +273,74 @@ mod tests {
 
     #[test]
     fn test_extract_base_command() {
+        let _guard = PATH_ENV_MUTEX.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
         let mut resolver = CommandResolver::new();
         // Should extract "cargo" from "cargo fmt"
         let resolution = resolver.resolve("cargo fmt --check");
         assert_eq!(resolution.command, "cargo");
     }
+
+    #[test]
+    fn test_platform_gate_rejects_mismatched_cfg() {
+        let _guard = PATH_ENV_MUTEX.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut resolver = CommandResolver::new();
+        resolver
+            .set_platform_gate("ls", r#"cfg(target_os = "definitely-not-a-real-os")"#)
+            .unwrap();
+        let resolution = resolver.resolve("ls");
+        assert!(!resolution.found);
+        assert!(resolution.reason.is_some());
+    }
+
+    #[test]
+    fn test_platform_gate_allows_matching_cfg() {
+        let _guard = PATH_ENV_MUTEX.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut resolver = CommandResolver::new();
+        resolver.set_platform_gate("ls", "cfg(unix)").unwrap();
+        let resolution = resolver.resolve("ls");
+        assert!(resolution.found);
+        assert!(resolution.reason.is_none());
+    }
+
+    #[test]
+    fn test_set_platform_gate_rejects_malformed_expr() {
+        let mut resolver = CommandResolver::new();
+        assert!(resolver.set_platform_gate("ls", "cfg(unix").is_err());
+    }
+
+    /// Write an executable stub named `name` into `dir`
+    #[cfg(unix)]
+    fn write_stub_binary(dir: &std::path::Path, name: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\nexit 0\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_resolve_all_reports_shadowed_duplicates() {
+        let _guard = PATH_ENV_MUTEX.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let shadowing_dir = tempfile::tempdir().unwrap();
+        let shadowed_dir = tempfile::tempdir().unwrap();
+        let shadowing_path = write_stub_binary(shadowing_dir.path(), "dupe_tool");
+        let shadowed_path = write_stub_binary(shadowed_dir.path(), "dupe_tool");
+
+        let original_path = std::env::var_os("PATH");
+        let new_path = std::env::join_paths([shadowing_dir.path(), shadowed_dir.path()]).unwrap();
+        std::env::set_var("PATH", &new_path);
+
+        let mut resolver = CommandResolver::new();
+        let matches = resolver.resolve_all("dupe_tool");
+
+        if let Some(path) = original_path {
+            std::env::set_var("PATH", path);
+        } else {
+            std::env::remove_var("PATH");
+        }
+
+        assert_eq!(matches, vec![shadowing_path, shadowed_path]);
+    }
 }
@@ -0,0 +1,256 @@
+//! crates.io sparse-index query tool: answers dependency, version, and
+//! feature questions from the registry's sparse index JSON lines
+//! (`{"name","vers","deps":[{"name","req","features","optional","kind"}]}`)
+//! instead of the agent shelling out to `curl` and parsing it inline. Caps
+//! the response size at its own ceiling and caches fetched shards
+//! in-process so repeated lookups during one session don't re-fetch.
+
+use super::traits::Tool;
+use crate::config::constants::tools;
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Response-size ceiling for one sparse index file (2 MiB, well above the
+/// `curl` tool's 64 KiB default); the sparse index serves newline-delimited
+/// JSON per published version and stays under this even for crates with
+/// hundreds of releases.
+const MAX_INDEX_BYTES: usize = 2 * 1024 * 1024;
+
+const SPARSE_INDEX_HOST: &str = "index.crates.io";
+
+/// One version record as published on the sparse index.
+#[derive(Debug, Clone, Deserialize)]
+struct IndexRecord {
+    vers: String,
+    #[serde(default)]
+    deps: Vec<IndexDependency>,
+    #[serde(default)]
+    features: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    yanked: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct IndexDependency {
+    name: String,
+    req: String,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    optional: bool,
+    kind: Option<String>,
+}
+
+fn default_field() -> String {
+    "all".to_string()
+}
+
+/// `crate_info` tool input.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CrateInfoInput {
+    pub crate_name: String,
+    /// Defaults to the newest non-yanked published version.
+    pub version: Option<String>,
+    /// `versions|deps|features|all`.
+    #[serde(default = "default_field")]
+    pub field: String,
+}
+
+/// Answers crates.io dependency/version/feature questions from the sparse
+/// index, caching fetched shards (keyed by crate name) for the process
+/// lifetime.
+#[derive(Clone)]
+pub struct CrateInfoTool {
+    client: reqwest::Client,
+    cache: Arc<Mutex<HashMap<String, Vec<IndexRecord>>>>,
+}
+
+impl CrateInfoTool {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    async fn fetch_records(&self, crate_name: &str) -> Result<Vec<IndexRecord>> {
+        {
+            let cache = self.cache.lock().await;
+            if let Some(records) = cache.get(crate_name) {
+                return Ok(records.clone());
+            }
+        }
+
+        let url = sparse_index_url(crate_name)?;
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("failed to fetch crates.io index for {crate_name}"))?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "crates.io index returned {} for {crate_name}",
+                response.status()
+            ));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .context("failed to read crates.io index response")?;
+        if bytes.len() > MAX_INDEX_BYTES {
+            return Err(anyhow!(
+                "crates.io index for {crate_name} exceeded {MAX_INDEX_BYTES} bytes"
+            ));
+        }
+
+        let mut records = Vec::new();
+        for line in bytes.split(|&byte| byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+            let record: IndexRecord = serde_json::from_slice(line)
+                .with_context(|| format!("malformed index record for {crate_name}"))?;
+            records.push(record);
+        }
+
+        self.cache
+            .lock()
+            .await
+            .insert(crate_name.to_string(), records.clone());
+        Ok(records)
+    }
+
+    async fn run(&self, input: &CrateInfoInput) -> Result<Value> {
+        let records = self.fetch_records(&input.crate_name).await?;
+        if records.is_empty() {
+            return Err(anyhow!(
+                "no index records found for crate `{}`",
+                input.crate_name
+            ));
+        }
+
+        let selected = match &input.version {
+            Some(version) => records.iter().find(|record| &record.vers == version).ok_or_else(|| {
+                anyhow!(
+                    "crate `{}` has no published version `{}`",
+                    input.crate_name,
+                    version
+                )
+            })?,
+            None => records
+                .iter()
+                .rev()
+                .find(|record| !record.yanked)
+                .unwrap_or_else(|| records.last().expect("records is non-empty")),
+        };
+
+        let versions: Vec<&str> = records.iter().map(|record| record.vers.as_str()).collect();
+        let deps: Vec<Value> = selected
+            .deps
+            .iter()
+            .map(|dep| {
+                json!({
+                    "name": dep.name,
+                    "req": dep.req,
+                    "features": dep.features,
+                    "optional": dep.optional,
+                    "kind": dep.kind.clone().unwrap_or_else(|| "normal".to_string()),
+                })
+            })
+            .collect();
+
+        Ok(match input.field.as_str() {
+            "versions" => json!({ "versions": versions }),
+            "deps" => json!({ "version": selected.vers, "deps": deps }),
+            "features" => json!({ "version": selected.vers, "features": selected.features }),
+            _ => json!({
+                "versions": versions,
+                "version": selected.vers,
+                "deps": deps,
+                "features": selected.features,
+            }),
+        })
+    }
+}
+
+/// Map a crate name to its path on the sparse index, following crates.io's
+/// length-based sharding: 1/2 char names live directly under `1/`/`2/`, 3
+/// char names get an extra directory level keyed by the first character,
+/// and everything else is sharded by its first four characters.
+fn sparse_index_url(crate_name: &str) -> Result<String> {
+    if crate_name.is_empty()
+        || !crate_name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+    {
+        return Err(anyhow!("invalid crate name: {crate_name}"));
+    }
+
+    let lower = crate_name.to_ascii_lowercase();
+    let path = match lower.len() {
+        1 => format!("1/{lower}"),
+        2 => format!("2/{lower}"),
+        3 => format!("3/{}/{lower}", &lower[..1]),
+        _ => format!("{}/{}/{lower}", &lower[..2], &lower[2..4]),
+    };
+
+    Ok(format!("https://{SPARSE_INDEX_HOST}/{path}"))
+}
+
+#[async_trait]
+impl Tool for CrateInfoTool {
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let input: CrateInfoInput = serde_json::from_value(args)?;
+        self.run(&input).await
+    }
+
+    fn name(&self) -> &'static str {
+        tools::CRATE_INFO
+    }
+
+    fn description(&self) -> &'static str {
+        "Look up a crate's versions, dependencies, and features from the crates.io sparse index"
+    }
+
+    fn validate_args(&self, args: &Value) -> Result<()> {
+        let _: CrateInfoInput = serde_json::from_value(args.clone())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shards_short_names_directly() {
+        assert_eq!(sparse_index_url("rq").unwrap(), "https://index.crates.io/2/rq");
+        assert_eq!(sparse_index_url("a").unwrap(), "https://index.crates.io/1/a");
+    }
+
+    #[test]
+    fn shards_three_char_names_by_first_letter() {
+        assert_eq!(sparse_index_url("cap").unwrap(), "https://index.crates.io/3/c/cap");
+    }
+
+    #[test]
+    fn shards_longer_names_by_first_four_chars() {
+        assert_eq!(
+            sparse_index_url("serde_json").unwrap(),
+            "https://index.crates.io/se/rd/serde_json"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_crate_names() {
+        assert!(sparse_index_url("").is_err());
+        assert!(sparse_index_url("../etc/passwd").is_err());
+    }
+}
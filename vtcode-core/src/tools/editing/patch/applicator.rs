@@ -7,104 +7,209 @@ use tokio::io::AsyncWriteExt;
 
 use super::error::PatchError;
 use super::matcher::PatchContextMatcher;
+use super::path::validate_resolved_patch_path;
 use super::{PatchChunk, PatchOperation};
 
+/// Snapshot of a single file's prior state, captured before the first
+/// operation in a transaction touches it, so the transaction can be undone
+/// if a later operation fails.
+struct FileSnapshot {
+    path: PathBuf,
+    /// `None` means the file did not exist before the transaction started.
+    previous: Option<Vec<u8>>,
+}
+
+async fn snapshot(path: &Path) -> Result<FileSnapshot, PatchError> {
+    let previous = match fs::read(path).await {
+        Ok(bytes) => Some(bytes),
+        Err(err) if err.kind() == ErrorKind::NotFound => None,
+        Err(err) => {
+            return Err(PatchError::Io {
+                action: "snapshot",
+                path: path.to_path_buf(),
+                source: err,
+            });
+        }
+    };
+    Ok(FileSnapshot {
+        path: path.to_path_buf(),
+        previous,
+    })
+}
+
+/// Restore every snapshot in reverse application order. Best-effort: this
+/// runs only after a failure, so individual restore errors are not fatal to
+/// the caller, which is already returning the original error.
+async fn rollback(snapshots: &[FileSnapshot]) {
+    for snapshot in snapshots.iter().rev() {
+        match &snapshot.previous {
+            Some(bytes) => {
+                let _ = write_atomic(&snapshot.path, bytes).await;
+            }
+            None => {
+                let _ = fs::remove_file(&snapshot.path).await;
+            }
+        }
+    }
+}
+
+/// The operation's name (for error messages) and the raw, workspace-relative
+/// paths it names, shared by [`apply`] (which needs to validate and then
+/// snapshot them) and [`apply_one`] (which needs to validate them again
+/// immediately before acting on them).
+fn operation_paths(operation: &PatchOperation) -> (&'static str, Vec<&str>) {
+    match operation {
+        PatchOperation::AddFile { path, .. } => ("add_file", vec![path.as_str()]),
+        PatchOperation::DeleteFile { path } => ("delete_file", vec![path.as_str()]),
+        PatchOperation::UpdateFile { path, new_path, .. } => {
+            let mut raw_paths = vec![path.as_str()];
+            if let Some(dest) = new_path {
+                raw_paths.push(dest.as_str());
+            }
+            ("update_file", raw_paths)
+        }
+    }
+}
+
+/// Apply every operation as a single all-or-nothing transaction. If any
+/// operation fails, every file touched by an earlier operation in this call
+/// is restored to its pre-transaction content (or deleted, if it didn't
+/// exist before) before the error is returned.
 pub(crate) async fn apply(
     root: &Path,
     operations: &[PatchOperation],
 ) -> Result<Vec<String>, PatchError> {
     let mut results = Vec::new();
+    let mut snapshots: Vec<FileSnapshot> = Vec::new();
 
     for operation in operations {
-        match operation {
-            PatchOperation::AddFile { path, content } => {
-                let full_path = root.join(path);
-                write_atomic(&full_path, content.as_bytes()).await?;
-                results.push(format!("Added file: {path}"));
+        let (operation_name, raw_paths) = operation_paths(operation);
+        for raw_path in &raw_paths {
+            if let Err(err) = validate_resolved_patch_path(operation_name, root, raw_path).await {
+                rollback(&snapshots).await;
+                return Err(err);
             }
-            PatchOperation::DeleteFile { path } => {
-                let full_path = root.join(path);
-                match fs::metadata(&full_path).await {
-                    Ok(metadata) => {
-                        if metadata.is_dir() {
-                            fs::remove_dir_all(&full_path)
-                                .await
-                                .map_err(|err| PatchError::Io {
-                                    action: "delete",
-                                    path: full_path.clone(),
-                                    source: err,
-                                })?;
-                        } else {
-                            fs::remove_file(&full_path)
-                                .await
-                                .map_err(|err| PatchError::Io {
-                                    action: "delete",
-                                    path: full_path.clone(),
-                                    source: err,
-                                })?;
-                        }
-                        results.push(format!("Deleted file: {path}"));
-                    }
-                    Err(err) if err.kind() == ErrorKind::NotFound => {
-                        results.push(format!("File not found, skipped deletion: {path}"));
-                    }
+        }
+
+        for raw_path in raw_paths {
+            let path = root.join(raw_path);
+            if !snapshots.iter().any(|existing| existing.path == path) {
+                match snapshot(&path).await {
+                    Ok(taken) => snapshots.push(taken),
                     Err(err) => {
-                        return Err(PatchError::Io {
-                            action: "inspect",
-                            path: full_path,
-                            source: err,
-                        });
+                        rollback(&snapshots).await;
+                        return Err(err);
                     }
                 }
             }
-            PatchOperation::UpdateFile {
-                path,
-                new_path,
-                chunks,
-            } => {
-                let source_path = root.join(path);
-                let existing =
-                    fs::read_to_string(&source_path)
-                        .await
-                        .map_err(|err| PatchError::Io {
-                            action: "read",
-                            path: source_path.clone(),
-                            source: err,
-                        })?;
-
-                let new_content = compute_new_content(&existing, path, chunks)?;
-
-                match new_path {
-                    Some(dest_rel) => {
-                        let dest_path = root.join(dest_rel);
-                        write_atomic(&dest_path, new_content.as_bytes()).await?;
-
-                        if dest_path != source_path {
-                            match fs::remove_file(&source_path).await {
-                                Ok(()) => {}
-                                Err(err) if err.kind() == ErrorKind::NotFound => {}
-                                Err(err) => {
-                                    return Err(PatchError::Io {
-                                        action: "delete",
-                                        path: source_path,
-                                        source: err,
-                                    });
-                                }
-                            }
-                        }
+        }
+
+        if let Err(err) = apply_one(root, operation, &mut results).await {
+            rollback(&snapshots).await;
+            return Err(err);
+        }
+    }
+
+    Ok(results)
+}
 
-                        results.push(format!("Updated file: {path} -> {dest_rel}"));
+async fn apply_one(
+    root: &Path,
+    operation: &PatchOperation,
+    results: &mut Vec<String>,
+) -> Result<(), PatchError> {
+    let (operation_name, raw_paths) = operation_paths(operation);
+    for raw_path in raw_paths {
+        validate_resolved_patch_path(operation_name, root, raw_path).await?;
+    }
+
+    match operation {
+        PatchOperation::AddFile { path, content } => {
+            let full_path = root.join(path);
+            write_atomic(&full_path, content.as_bytes()).await?;
+            results.push(format!("Added file: {path}"));
+        }
+        PatchOperation::DeleteFile { path } => {
+            let full_path = root.join(path);
+            match fs::metadata(&full_path).await {
+                Ok(metadata) => {
+                    if metadata.is_dir() {
+                        fs::remove_dir_all(&full_path)
+                            .await
+                            .map_err(|err| PatchError::Io {
+                                action: "delete",
+                                path: full_path.clone(),
+                                source: err,
+                            })?;
+                    } else {
+                        fs::remove_file(&full_path)
+                            .await
+                            .map_err(|err| PatchError::Io {
+                                action: "delete",
+                                path: full_path.clone(),
+                                source: err,
+                            })?;
                     }
-                    None => {
-                        write_atomic(&source_path, new_content.as_bytes()).await?;
-                        results.push(format!("Updated file: {path}"));
+                    results.push(format!("Deleted file: {path}"));
+                }
+                Err(err) if err.kind() == ErrorKind::NotFound => {
+                    results.push(format!("File not found, skipped deletion: {path}"));
+                }
+                Err(err) => {
+                    return Err(PatchError::Io {
+                        action: "inspect",
+                        path: full_path,
+                        source: err,
+                    });
+                }
+            }
+        }
+        PatchOperation::UpdateFile {
+            path,
+            new_path,
+            chunks,
+        } => {
+            let source_path = root.join(path);
+            let existing = fs::read_to_string(&source_path)
+                .await
+                .map_err(|err| PatchError::Io {
+                    action: "read",
+                    path: source_path.clone(),
+                    source: err,
+                })?;
+
+            let new_content = compute_new_content(&existing, path, chunks)?;
+
+            match new_path {
+                Some(dest_rel) => {
+                    let dest_path = root.join(dest_rel);
+                    write_atomic(&dest_path, new_content.as_bytes()).await?;
+
+                    if dest_path != source_path {
+                        match fs::remove_file(&source_path).await {
+                            Ok(()) => {}
+                            Err(err) if err.kind() == ErrorKind::NotFound => {}
+                            Err(err) => {
+                                return Err(PatchError::Io {
+                                    action: "delete",
+                                    path: source_path,
+                                    source: err,
+                                });
+                            }
+                        }
                     }
+
+                    results.push(format!("Updated file: {path} -> {dest_rel}"));
+                }
+                None => {
+                    write_atomic(&source_path, new_content.as_bytes()).await?;
+                    results.push(format!("Updated file: {path}"));
                 }
             }
         }
     }
 
-    Ok(results)
+    Ok(())
 }
 
 fn compute_new_content(
@@ -1,4 +1,6 @@
-use std::path::{Component, Path};
+use std::path::{Component, Path, PathBuf};
+
+use tokio::fs;
 
 use super::error::PatchError;
 
@@ -57,3 +59,58 @@ pub(crate) fn validate_patch_path(
 
     Ok(())
 }
+
+/// Walk `raw_path` component by component under `root`, following any
+/// pre-existing symlinks along the way, and fail if one resolves outside
+/// `root`. This catches the case `validate_patch_path` can't: a relative
+/// path whose lexical form looks safe but that passes through a symlink
+/// (e.g. `link/evil` where `link` -> `/etc`) planted before the patch runs.
+///
+/// Only components that actually exist on disk are resolved; components the
+/// patch is about to create (e.g. the final segment of an `AddFile`) are
+/// skipped, since they can't yet be a symlink.
+pub(crate) async fn validate_resolved_patch_path(
+    operation: &'static str,
+    root: &Path,
+    raw_path: &str,
+) -> Result<(), PatchError> {
+    validate_patch_path(operation, raw_path)?;
+
+    let root = fs::canonicalize(root)
+        .await
+        .map_err(|err| PatchError::Io {
+            action: "canonicalize",
+            path: root.to_path_buf(),
+            source: err,
+        })?;
+
+    let mut walked = PathBuf::new();
+    for component in Path::new(raw_path).components() {
+        walked.push(component);
+        let candidate = root.join(&walked);
+
+        let metadata = match fs::symlink_metadata(&candidate).await {
+            Ok(metadata) => metadata,
+            Err(_) => continue, // Doesn't exist yet; nothing to escape through.
+        };
+
+        if metadata.is_symlink() {
+            let resolved = fs::canonicalize(&candidate)
+                .await
+                .map_err(|err| PatchError::Io {
+                    action: "resolve symlink",
+                    path: candidate.clone(),
+                    source: err,
+                })?;
+
+            if !resolved.starts_with(&root) {
+                return Err(PatchError::SymlinkEscape {
+                    path: raw_path.to_string(),
+                    resolved,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+//! File-type classification shared by the tool layer and the renderer
+//!
+//! Borrows ripgrep's approach of a built-in, lexicographically-sorted type
+//! map (extension/glob -> type name) so directory listings, diffs, and file
+//! previews can all agree on what counts as "source", "config", "image",
+//! and so on, with a single place to add user overrides.
+
+use std::collections::BTreeMap;
+
+/// Coarse category a file name is classified into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileCategory {
+    Source,
+    Config,
+    Docs,
+    Archive,
+    Image,
+    Binary,
+    Other,
+}
+
+impl FileCategory {
+    /// Machine-readable label, e.g. for display alongside `encoding`/`size`
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileCategory::Source => "source",
+            FileCategory::Config => "config",
+            FileCategory::Docs => "docs",
+            FileCategory::Archive => "archive",
+            FileCategory::Image => "image",
+            FileCategory::Binary => "binary",
+            FileCategory::Other => "other",
+        }
+    }
+}
+
+/// Built-in extension -> category map, kept lexicographically sorted by
+/// extension so additions are easy to place and diff cleanly
+const DEFAULT_EXTENSIONS: &[(&str, FileCategory)] = &[
+    ("7z", FileCategory::Archive),
+    ("bmp", FileCategory::Image),
+    ("bz2", FileCategory::Archive),
+    ("c", FileCategory::Source),
+    ("cpp", FileCategory::Source),
+    ("exe", FileCategory::Binary),
+    ("gif", FileCategory::Image),
+    ("go", FileCategory::Source),
+    ("gz", FileCategory::Archive),
+    ("h", FileCategory::Source),
+    ("ico", FileCategory::Image),
+    ("jpeg", FileCategory::Image),
+    ("jpg", FileCategory::Image),
+    ("js", FileCategory::Source),
+    ("json", FileCategory::Config),
+    ("jsx", FileCategory::Source),
+    ("md", FileCategory::Docs),
+    ("png", FileCategory::Image),
+    ("py", FileCategory::Source),
+    ("rs", FileCategory::Source),
+    ("rst", FileCategory::Docs),
+    ("so", FileCategory::Binary),
+    ("svg", FileCategory::Image),
+    ("tar", FileCategory::Archive),
+    ("toml", FileCategory::Config),
+    ("ts", FileCategory::Source),
+    ("tsx", FileCategory::Source),
+    ("txt", FileCategory::Docs),
+    ("webp", FileCategory::Image),
+    ("xz", FileCategory::Archive),
+    ("yaml", FileCategory::Config),
+    ("yml", FileCategory::Config),
+    ("zip", FileCategory::Archive),
+];
+
+/// Built-in glob -> category map for names that aren't well described by
+/// their extension alone (dotfiles, well-known config file names)
+const DEFAULT_GLOBS: &[(&str, FileCategory)] = &[
+    (".env", FileCategory::Config),
+    (".gitignore", FileCategory::Config),
+    ("Cargo.lock", FileCategory::Config),
+    ("Cargo.toml", FileCategory::Config),
+    ("Dockerfile", FileCategory::Config),
+    ("LICENSE", FileCategory::Docs),
+    ("Makefile", FileCategory::Config),
+    ("README*", FileCategory::Docs),
+];
+
+/// User-supplied rules layered over the built-in defaults. Both maps take
+/// priority over `DEFAULT_EXTENSIONS`/`DEFAULT_GLOBS`, with glob rules
+/// checked before extension rules since they can match a full file name.
+#[derive(Debug, Clone, Default)]
+pub struct FileTypeOverrides {
+    /// `extension -> type name` rules, extension given without a leading dot
+    pub extensions: BTreeMap<String, String>,
+    /// `glob -> type name` rules, matched with simple `*`-wildcard globbing
+    pub globs: BTreeMap<String, String>,
+}
+
+/// Classify `name` into a [`FileCategory`], checking `overrides` (if any)
+/// before falling back to the built-in glob and extension maps, and finally
+/// to [`FileCategory::Other`] for anything unrecognized.
+pub fn classify_file_type(name: &str, overrides: Option<&FileTypeOverrides>) -> FileCategory {
+    if let Some(overrides) = overrides {
+        for (glob, label) in &overrides.globs {
+            if glob_matches(glob, name) {
+                return category_from_label(label);
+            }
+        }
+        if let Some(ext) = extension_of(name) {
+            if let Some(label) = overrides.extensions.get(ext) {
+                return category_from_label(label);
+            }
+        }
+    }
+
+    for (glob, category) in DEFAULT_GLOBS {
+        if glob_matches(glob, name) {
+            return *category;
+        }
+    }
+
+    if let Some(ext) = extension_of(name) {
+        if let Ok(index) = DEFAULT_EXTENSIONS.binary_search_by_key(&ext, |(ext, _)| ext) {
+            return DEFAULT_EXTENSIONS[index].1;
+        }
+    }
+
+    FileCategory::Other
+}
+
+fn extension_of(name: &str) -> Option<&str> {
+    name.rsplit_once('.').map(|(_, ext)| ext).filter(|ext| !ext.is_empty())
+}
+
+/// Map a user override's free-form type name to the closest [`FileCategory`],
+/// defaulting to [`FileCategory::Other`] for unrecognized labels
+fn category_from_label(label: &str) -> FileCategory {
+    match label.to_ascii_lowercase().as_str() {
+        "source" => FileCategory::Source,
+        "config" => FileCategory::Config,
+        "docs" => FileCategory::Docs,
+        "archive" => FileCategory::Archive,
+        "image" => FileCategory::Image,
+        "binary" => FileCategory::Binary,
+        _ => FileCategory::Other,
+    }
+}
+
+/// Minimal glob matcher supporting a single trailing `*` wildcard, enough
+/// for patterns like `README*`; anything else is matched literally
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_classifications() {
+        assert_eq!(classify_file_type("main.rs", None), FileCategory::Source);
+        assert_eq!(classify_file_type("Cargo.toml", None), FileCategory::Config);
+        assert_eq!(classify_file_type("archive.tar.gz", None), FileCategory::Archive);
+        assert_eq!(classify_file_type("logo.svg", None), FileCategory::Image);
+        assert_eq!(classify_file_type("README.md", None), FileCategory::Docs);
+        assert_eq!(classify_file_type("Dockerfile", None), FileCategory::Config);
+    }
+
+    #[test]
+    fn unknown_extension_falls_back_to_other() {
+        assert_eq!(classify_file_type("data.xyz123", None), FileCategory::Other);
+        assert_eq!(classify_file_type("noextension", None), FileCategory::Other);
+    }
+
+    #[test]
+    fn user_overrides_take_priority_over_defaults() {
+        let mut overrides = FileTypeOverrides::default();
+        overrides.extensions.insert("rs".to_string(), "docs".to_string());
+        overrides.globs.insert("special.toml".to_string(), "source".to_string());
+
+        assert_eq!(
+            classify_file_type("main.rs", Some(&overrides)),
+            FileCategory::Docs
+        );
+        assert_eq!(
+            classify_file_type("special.toml", Some(&overrides)),
+            FileCategory::Source
+        );
+        // Unrelated extensions still fall back to the defaults
+        assert_eq!(
+            classify_file_type("Cargo.toml", Some(&overrides)),
+            FileCategory::Config
+        );
+    }
+}
@@ -3,10 +3,94 @@ use std::collections::HashMap;
 use crate::config::constants::tools;
 use crate::config::types::CapabilityLevel;
 use crate::gemini::FunctionDeclaration;
-use serde_json::json;
+use serde_json::{json, Map, Value};
 
 use super::builtins::builtin_tool_registrations;
 
+/// Named JSON Schema fragments shared by several tools' `parameters`, kept in
+/// one place instead of the same blob (e.g. `response_format: concise|detailed`)
+/// being pasted into every declaration that takes it. Declarations reference
+/// these as `{"$ref": "#/$defs/<Name>"}`; [`inline_refs`] expands the refs
+/// back out for backends that don't resolve `$ref` themselves.
+fn schema_defs() -> Map<String, Value> {
+    json!({
+        "ResponseFormat": {"type": "string", "description": "Format: concise|detailed", "default": "concise"},
+        "WorkingDir": {"type": "string", "description": "Working directory"},
+        "TimeoutSecs": {"type": "integer", "description": "Timeout (seconds)", "default": 30, "minimum": 1, "maximum": 3600},
+        "Page": {"type": "integer", "description": "Page number (1-based)", "default": 1},
+        "PerPage": {"type": "integer", "description": "Items per page", "default": 50},
+        "MaxResults": {"type": "integer", "description": "Max results", "default": 100},
+        "Command": {
+            "description": "Command to run",
+            "oneOf": [
+                {"type": "array", "items": {"type": "string"}},
+                {"type": "string"}
+            ]
+        }
+    })
+    .as_object()
+    .expect("schema_defs literal is a JSON object")
+    .clone()
+}
+
+/// Attach the shared `$defs` table to every declaration's `parameters`
+/// object so `{"$ref": "#/$defs/X"}` entries resolve against a
+/// self-contained document for backends that honor `$ref`.
+fn attach_shared_defs(declarations: &mut [FunctionDeclaration]) {
+    let defs = Value::Object(schema_defs());
+    for decl in declarations {
+        if let Some(params) = decl.parameters.as_object_mut() {
+            params.insert("$defs".to_string(), defs.clone());
+        }
+    }
+}
+
+/// Recursively expand every `{"$ref": "#/$defs/<Name>"}` in `decl.parameters`
+/// against the shared [`schema_defs`] table, for function-calling backends
+/// (e.g. Gemini's `FunctionDeclaration`) that don't resolve `$ref`
+/// themselves. Tracks visited pointers per expansion chain so a `$defs`
+/// table with a ref cycle can't recurse forever.
+pub fn inline_refs(decl: &mut FunctionDeclaration) {
+    let defs = schema_defs();
+    inline_refs_value(&mut decl.parameters, &defs, &mut Vec::new());
+    if let Some(params) = decl.parameters.as_object_mut() {
+        params.remove("$defs");
+    }
+}
+
+fn inline_refs_value(value: &mut Value, defs: &Map<String, Value>, visited: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(pointer)) = map.get("$ref").cloned() {
+                if visited.contains(&pointer) {
+                    // Cyclical $ref: leave the pointer in place rather than
+                    // expanding forever.
+                    return;
+                }
+                if let Some(name) = pointer.strip_prefix("#/$defs/") {
+                    if let Some(fragment) = defs.get(name) {
+                        visited.push(pointer.clone());
+                        let mut expanded = fragment.clone();
+                        inline_refs_value(&mut expanded, defs, visited);
+                        visited.pop();
+                        *value = expanded;
+                        return;
+                    }
+                }
+            }
+            for entry in map.values_mut() {
+                inline_refs_value(entry, defs, visited);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                inline_refs_value(item, defs, visited);
+            }
+        }
+        _ => {}
+    }
+}
+
 fn base_function_declarations() -> Vec<FunctionDeclaration> {
     vec![
         // Search Tools
@@ -18,15 +102,15 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
                 "properties": {
                     "pattern": {"type": "string", "description": "Search pattern (e.g., 'fn \\w+', 'TODO|FIXME')"},
                     "path": {"type": "string", "description": "Directory to search (relative)", "default": "."},
-                    "mode": {"type": "string", "description": "Mode: exact|fuzzy|multi|similarity", "default": "exact"},
-                    "max_results": {"type": "integer", "description": "Max results", "default": 100},
+                    "mode": {"type": "string", "enum": ["exact", "fuzzy", "multi", "similarity"], "description": "Mode: exact|fuzzy|multi|similarity", "default": "exact"},
+                    "max_results": {"$ref": "#/$defs/MaxResults"},
                     "case_sensitive": {"type": "boolean", "description": "Case sensitive", "default": true},
                     "patterns": {"type": "array", "items": {"type": "string"}, "description": "For mode=multi"},
                     "logic": {"type": "string", "description": "For mode=multi: AND|OR", "default": "AND"},
-                    "fuzzy_threshold": {"type": "number", "description": "Fuzzy threshold (0.0-1.0)", "default": 0.7},
-                    "reference_file": {"type": "string", "description": "For mode=similarity"},
+                    "fuzzy_threshold": {"type": "number", "description": "Fuzzy threshold (0.0-1.0)", "default": 0.7, "minimum": 0.0, "maximum": 1.0},
+                    "reference_file": {"type": ["string", "null"], "description": "For mode=similarity"},
                     "content_type": {"type": "string", "description": "For similarity: structure|imports|functions|all", "default": "all"},
-                    "response_format": {"type": "string", "description": "Format: concise|detailed", "default": "concise"}
+                    "response_format": {"$ref": "#/$defs/ResponseFormat"}
                 },
                 "required": ["pattern"]
             }),
@@ -41,9 +125,9 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
                     "path": {"type": "string", "description": "Search path (relative)"},
                     "mode": {"type": "string", "description": "Mode: list|recursive|find_name|find_content|largest", "default": "list"},
                     "max_items": {"type": "integer", "description": "Max items scanned", "default": 1000},
-                    "page": {"type": "integer", "description": "Page number (1-based)", "default": 1},
-                    "per_page": {"type": "integer", "description": "Items per page", "default": 50},
-                    "response_format": {"type": "string", "description": "Format: concise|detailed", "default": "concise"},
+                    "page": {"$ref": "#/$defs/Page"},
+                    "per_page": {"$ref": "#/$defs/PerPage"},
+                    "response_format": {"$ref": "#/$defs/ResponseFormat"},
                     "include_hidden": {"type": "boolean", "description": "Include hidden files", "default": false},
                     "name_pattern": {"type": "string", "description": "Pattern (e.g., *.rs)", "default": "*"},
                     "content_pattern": {"type": "string", "description": "Content pattern for find_content mode"},
@@ -136,24 +220,18 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
             parameters: json!({
                 "type": "object",
                 "properties": {
-                    "command": {
-                        "description": "Command to run",
-                        "oneOf": [
-                            {"type": "array", "items": {"type": "string"}},
-                            {"type": "string"}
-                        ]
-                    },
-                    "working_dir": {"type": "string", "description": "Working directory"},
+                    "command": {"$ref": "#/$defs/Command"},
+                    "working_dir": {"$ref": "#/$defs/WorkingDir"},
                     "cwd": {"type": "string", "description": "Alias for working_dir"},
-                    "timeout_secs": {"type": "integer", "description": "Timeout (seconds)", "default": 30},
+                    "timeout_secs": {"$ref": "#/$defs/TimeoutSecs"},
                     "timeout": {
                         "oneOf": [{"type": "integer"}, {"type": "number"}],
                         "description": "Alias for timeout_secs"
                     },
-                    "mode": {"type": "string", "description": "Mode: terminal|pty", "default": "terminal"},
+                    "mode": {"type": "string", "enum": ["terminal", "pty"], "description": "Mode: terminal|pty", "default": "terminal"},
                     "tty": {"type": "boolean", "description": "Alias for mode=pty"},
-                    "response_format": {"type": "string", "description": "Format: concise|detailed", "default": "concise"},
-                    "shell": {"type": "string", "description": "Shell executable"},
+                    "response_format": {"$ref": "#/$defs/ResponseFormat"},
+                    "shell": {"type": ["string", "null"], "description": "Shell executable"},
                     "login": {"type": "boolean", "description": "Use login shell"}
                 },
                 "required": ["command"]
@@ -167,22 +245,16 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
             parameters: json!({
                 "type": "object",
                 "properties": {
-                    "command": {
-                        "description": "Command to run",
-                        "oneOf": [
-                            {"type": "string"},
-                            {"type": "array", "items": {"type": "string"}}
-                        ]
-                    },
+                    "command": {"$ref": "#/$defs/Command"},
                     "args": {
                         "type": "array",
                         "items": {"type": "string"},
                         "description": "Command arguments"
                     },
-                    "working_dir": {"type": "string", "description": "Working directory"},
+                    "working_dir": {"$ref": "#/$defs/WorkingDir"},
                     "timeout_secs": {"type": "integer", "description": "Timeout (seconds)", "default": 300},
-                    "rows": {"type": "integer", "description": "Terminal rows", "default": 24},
-                    "cols": {"type": "integer", "description": "Terminal columns", "default": 80}
+                    "rows": {"type": "integer", "description": "Terminal rows", "default": 24, "minimum": 1, "maximum": 500},
+                    "cols": {"type": "integer", "description": "Terminal columns", "default": 80, "minimum": 1, "maximum": 1000}
                 },
                 "required": ["command"]
             }),
@@ -207,9 +279,9 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
                         "items": {"type": "string"},
                         "description": "Command arguments"
                     },
-                    "working_dir": {"type": "string", "description": "Working directory"},
-                    "rows": {"type": "integer", "description": "Terminal rows", "default": 24},
-                    "cols": {"type": "integer", "description": "Terminal columns", "default": 80}
+                    "working_dir": {"$ref": "#/$defs/WorkingDir"},
+                    "rows": {"type": "integer", "description": "Terminal rows", "default": 24, "minimum": 1, "maximum": 500},
+                    "cols": {"type": "integer", "description": "Terminal columns", "default": 80, "minimum": 1, "maximum": 1000}
                 },
                 "required": ["session_id", "command"]
             }),
@@ -278,14 +350,45 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
                 "type": "object",
                 "properties": {
                     "session_id": {"type": "string", "description": "Session ID"},
-                    "rows": {"type": "integer", "description": "Rows", "minimum": 1},
-                    "cols": {"type": "integer", "description": "Columns", "minimum": 1}
+                    "rows": {"type": "integer", "description": "Rows", "minimum": 1, "maximum": 500},
+                    "cols": {"type": "integer", "description": "Columns", "minimum": 1, "maximum": 1000}
                 },
                 "required": ["session_id"],
                 "additionalProperties": false
             }),
         },
 
+        // File Watching
+        FunctionDeclaration {
+            name: tools::WATCH_FILES.to_string(),
+            description: "Watch paths and re-run a command on a debounced batch of matching filesystem changes.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "paths": {"type": "array", "items": {"type": "string"}, "description": "Paths to watch, recursively"},
+                    "filter_extensions": {"type": "array", "items": {"type": "string"}, "description": "Only react to these extensions (e.g. [\"rs\"])"},
+                    "ignore_patterns": {"type": "array", "items": {"type": "string"}, "description": "Glob patterns to ignore in addition to VCS ignore rules"},
+                    "no_vcs_ignore": {"type": "boolean", "description": "Don't honor .gitignore", "default": false},
+                    "debounce_ms": {"type": "integer", "description": "Quiet period before running, in ms", "default": 50},
+                    "filter_fs_events": {
+                        "type": "array",
+                        "items": {"type": "string", "enum": ["Create", "Remove", "Rename", "Modify", "Metadata"]},
+                        "description": "Event kinds that count as a trigger"
+                    },
+                    "on_busy_update": {
+                        "type": "string",
+                        "enum": ["restart", "queue", "do-nothing"],
+                        "description": "Accepted but has no effect: each run finishes before the next batch is read",
+                        "default": "restart"
+                    },
+                    "command": {"type": "array", "items": {"type": "string"}, "description": "Command to run on each triggering batch"},
+                    "stop_signal": {"type": "string", "description": "Signal for stopping a running child (e.g. SIGTERM); not sent, since each run finishes before the next starts"},
+                    "stop_timeout_secs": {"type": "integer", "description": "Wait before a hard kill after stop_signal (unused while stop_signal is not sent)", "default": 5}
+                },
+                "required": ["paths", "command"]
+            }),
+        },
+
         // Network Operations
         FunctionDeclaration {
             name: tools::CURL.to_string(),
@@ -303,6 +406,20 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
             }),
         },
 
+        FunctionDeclaration {
+            name: tools::CRATE_INFO.to_string(),
+            description: "Look up a crate's versions, dependencies, and features from the crates.io sparse index.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "crate_name": {"type": "string", "description": "Crate name on crates.io"},
+                    "version": {"type": ["string", "null"], "description": "Specific version (default: latest non-yanked)"},
+                    "field": {"type": "string", "enum": ["versions", "deps", "features", "all"], "description": "Which part of the record to return", "default": "all"}
+                },
+                "required": ["crate_name"]
+            }),
+        },
+
         // Code Analysis
         FunctionDeclaration {
             name: tools::AST_GREP_SEARCH.to_string(),
@@ -317,7 +434,7 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
                     "replacement": {"type": "string", "description": "Replacement pattern"},
                     "refactor_type": {"type": "string", "description": "Refactor type"},
                     "context_lines": {"type": "integer", "description": "Context lines", "default": 0},
-                    "max_results": {"type": "integer", "description": "Max results", "default": 100},
+                    "max_results": {"$ref": "#/$defs/MaxResults"},
                     "preview_only": {"type": "boolean", "description": "Preview only", "default": true},
                     "update_all": {"type": "boolean", "description": "Update all matches", "default": false},
                     "interactive": {"type": "boolean", "description": "Interactive mode", "default": false},
@@ -389,6 +506,20 @@ fn base_function_declarations() -> Vec<FunctionDeclaration> {
             }),
         },
 
+        // Introspection
+        FunctionDeclaration {
+            name: tools::DESCRIBE_TOOLS.to_string(),
+            description: "List the agent's own tools as a machine-readable manifest (name, description, normalized options, required fields, capability level).".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "capability_level": {"type": "string", "description": "Only list tools at or below this CapabilityLevel"},
+                    "name_filter": {"type": "string", "description": "Substring filter over tool names"}
+                },
+                "required": []
+            }),
+        },
+
         // Planning
         FunctionDeclaration {
             name: tools::UPDATE_PLAN.to_string(),
@@ -436,12 +567,143 @@ pub fn build_function_declarations_with_mode(
     todo_planning_enabled: bool,
 ) -> Vec<FunctionDeclaration> {
     let mut declarations = base_function_declarations();
+    attach_shared_defs(&mut declarations);
     if !todo_planning_enabled {
         declarations.retain(|decl| decl.name != tools::UPDATE_PLAN);
     }
     declarations
 }
 
+/// [`build_function_declarations_with_mode`]'s declarations tagged with the
+/// [`SchemaVersion`] they were built at, so a caller persisting or diffing
+/// tool schemas across runs doesn't have to track the version out of band.
+#[derive(Debug, Clone)]
+pub struct VersionedDeclarations {
+    pub schema_version: String,
+    pub declarations: Vec<FunctionDeclaration>,
+}
+
+/// Same declarations as [`build_function_declarations_with_mode`], tagged
+/// with [`CURRENT_SCHEMA_VERSION`].
+pub fn build_function_declarations_with_schema_version(
+    todo_planning_enabled: bool,
+) -> VersionedDeclarations {
+    VersionedDeclarations {
+        schema_version: CURRENT_SCHEMA_VERSION.as_str().to_string(),
+        declarations: build_function_declarations_with_mode(todo_planning_enabled),
+    }
+}
+
+/// Same as [`build_function_declarations_with_mode`], but for backends that
+/// don't resolve `$ref` (e.g. Gemini's `FunctionDeclaration`): when
+/// `supports_refs` is `false`, every declaration is passed through
+/// [`inline_refs`] so the emitted schema is self-contained instead of
+/// sending keys the provider can't follow.
+pub fn build_function_declarations_for_provider(
+    todo_planning_enabled: bool,
+    supports_refs: bool,
+) -> Vec<FunctionDeclaration> {
+    let mut declarations = build_function_declarations_with_mode(todo_planning_enabled);
+    if !supports_refs {
+        for decl in &mut declarations {
+            inline_refs(decl);
+        }
+    }
+    declarations
+}
+
+/// A tool-schema revision, modeled on Compose's `version: "3.12"` evolution:
+/// each later version is a superset of the last, and [`VERSIONED_PROPERTIES`]
+/// records which version introduced which (tool, property) pair so
+/// [`build_function_declarations_for_version`] can down-level for a
+/// provider/model that only understands an older schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SchemaVersion {
+    /// The original, pre-PTY-scrollback, pre-grep-similarity, pre-`save_response` schema.
+    V1,
+    /// Current schema: adds PTY `include_scrollback`, grep `similarity` mode, and curl `save_response`.
+    V2,
+}
+
+/// The schema version emitted by [`build_function_declarations_with_mode`]
+/// and friends when no older version is explicitly requested.
+pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion::V2;
+
+impl SchemaVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::V1 => "1.0",
+            Self::V2 => "2.0",
+        }
+    }
+}
+
+/// `(tool name, property name) -> version introduced`. A property absent
+/// from this table is assumed to have existed since [`SchemaVersion::V1`].
+const VERSIONED_PROPERTIES: &[(&str, &str, SchemaVersion)] = &[
+    (tools::READ_PTY_SESSION, "include_scrollback", SchemaVersion::V2),
+    (tools::GREP_FILE, "reference_file", SchemaVersion::V2),
+    (tools::GREP_FILE, "content_type", SchemaVersion::V2),
+    (tools::CURL, "save_response", SchemaVersion::V2),
+];
+
+/// The declarations a caller would get from [`build_function_declarations`],
+/// down-leveled to `version`: properties introduced after `version` (per
+/// [`VERSIONED_PROPERTIES`]) are dropped from `properties`/`required` rather
+/// than sent as schema keys the target doesn't understand.
+pub fn build_function_declarations_for_version(version: SchemaVersion) -> Vec<FunctionDeclaration> {
+    let mut declarations = build_function_declarations_with_mode(true);
+    for decl in &mut declarations {
+        downlevel_declaration(decl, version);
+    }
+    declarations
+}
+
+fn downlevel_declaration(decl: &mut FunctionDeclaration, version: SchemaVersion) {
+    let dropped: Vec<&str> = VERSIONED_PROPERTIES
+        .iter()
+        .filter(|(tool, _, introduced)| *tool == decl.name && *introduced > version)
+        .map(|(_, property, _)| *property)
+        .collect();
+    if dropped.is_empty() {
+        return;
+    }
+
+    if let Some(properties) = decl
+        .parameters
+        .get_mut("properties")
+        .and_then(Value::as_object_mut)
+    {
+        for property in &dropped {
+            properties.remove(*property);
+        }
+    }
+    if let Some(required) = decl
+        .parameters
+        .get_mut("required")
+        .and_then(Value::as_array_mut)
+    {
+        required.retain(|entry| !dropped.iter().any(|property| entry.as_str() == Some(property)));
+    }
+
+    // grep's `similarity` mode only makes sense with `reference_file` and
+    // `content_type`, both dropped above for V1; stop advertising it rather
+    // than leaving a mode value the snippet has no parameters to drive.
+    if decl.name == tools::GREP_FILE {
+        if let Some(mode) = decl.parameters.pointer_mut("/properties/mode") {
+            if let Some(mode) = mode.as_object_mut() {
+                mode.insert(
+                    "description".to_string(),
+                    json!("Mode: exact|fuzzy|multi"),
+                );
+                if let Some(Value::Array(variants)) = mode.get_mut("enum") {
+                    variants.retain(|variant| variant.as_str() != Some("similarity"));
+                }
+            }
+        }
+    }
+}
+
 /// Build function declarations filtered by capability level
 pub fn build_function_declarations_for_level(level: CapabilityLevel) -> Vec<FunctionDeclaration> {
     let tool_capabilities: HashMap<&'static str, CapabilityLevel> = builtin_tool_registrations()
@@ -460,3 +722,103 @@ pub fn build_function_declarations_for_level(level: CapabilityLevel) -> Vec<Func
         })
         .collect()
 }
+
+/// Option names that refer to the same underlying knob under an alias used by
+/// a subset of tools (`working_dir`/`cwd`, `timeout_secs`/`timeout`); the
+/// first entry in each group is the canonical name reported by
+/// [`tool_manifest`].
+const OPTION_ALIAS_GROUPS: &[&[&str]] = &[&["working_dir", "cwd"], &["timeout_secs", "timeout"]];
+
+fn canonical_option_name(name: &str) -> &str {
+    OPTION_ALIAS_GROUPS
+        .iter()
+        .find(|group| group.contains(&name))
+        .map(|group| group[0])
+        .unwrap_or(name)
+}
+
+/// Flatten a `FunctionDeclaration`'s JSON Schema `properties` into the
+/// manifest's `options` array, collapsing alias pairs from
+/// [`OPTION_ALIAS_GROUPS`] into one entry (`aliases` carries the other
+/// names) instead of listing `working_dir` and `cwd` as unrelated options.
+fn normalize_options(parameters: &Value, required: &[String]) -> Vec<Value> {
+    let Some(properties) = parameters.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    let mut canonical_order: Vec<&str> = Vec::new();
+    let mut aliases: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut schemas: HashMap<&str, &Value> = HashMap::new();
+
+    for (name, schema) in properties {
+        let canonical = canonical_option_name(name);
+        if !schemas.contains_key(canonical) {
+            canonical_order.push(canonical);
+        }
+        schemas.entry(canonical).or_insert(schema);
+        if canonical != name {
+            aliases.entry(canonical).or_default().push(name.as_str());
+        }
+    }
+
+    canonical_order
+        .into_iter()
+        .map(|name| {
+            json!({
+                "name": name,
+                "aliases": aliases.get(name).cloned().unwrap_or_default(),
+                "schema": schemas[name],
+                "required": required.iter().any(|r| r == name),
+            })
+        })
+        .collect()
+}
+
+/// Emit the tool registry as a nested tree analogous to `ipfs commands
+/// --json`: each entry carries its `name`, `description`, a normalized
+/// `options` array (see [`normalize_options`]), `required` field names, and
+/// `capability`, so external orchestrators and shell-completion generators
+/// can enumerate the agent's tools without parsing raw JSON Schema.
+/// Honors `todo_planning_enabled` and capability filtering the same way
+/// [`build_function_declarations_for_level`] does.
+pub fn tool_manifest(level: CapabilityLevel, todo_planning_enabled: bool) -> Value {
+    let tool_capabilities: HashMap<&'static str, CapabilityLevel> = builtin_tool_registrations()
+        .into_iter()
+        .filter(|registration| registration.expose_in_llm())
+        .map(|registration| (registration.name(), registration.capability()))
+        .collect();
+
+    let entries: Vec<Value> = build_function_declarations_for_provider(todo_planning_enabled, false)
+        .into_iter()
+        .filter_map(|decl| {
+            let capability = *tool_capabilities.get(decl.name.as_str())?;
+            if level < capability {
+                return None;
+            }
+
+            let required: Vec<String> = decl
+                .parameters
+                .get("required")
+                .and_then(Value::as_array)
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let options = normalize_options(&decl.parameters, &required);
+
+            Some(json!({
+                "name": decl.name,
+                "description": decl.description,
+                "options": options,
+                "required": required,
+                "capability": format!("{capability:?}"),
+            }))
+        })
+        .collect();
+
+    json!({ "tools": entries })
+}
@@ -0,0 +1,102 @@
+//! Validates incoming tool arguments against each tool's JSON Schema before
+//! dispatch, so a model passing `mode: "fuzy"` (or omitting a required
+//! field) fails with a structured, path-annotated error it can self-correct
+//! from instead of an opaque failure deep inside the tool.
+
+use std::collections::HashMap;
+
+use super::declarations::build_function_declarations_for_provider;
+use anyhow::{Context, Result};
+use jsonschema::JSONSchema;
+use serde_json::Value;
+
+/// One schema violation: `path` is the JSON Pointer into the arguments
+/// object where the violation occurred (e.g. `/mode`), `message` is the
+/// human-readable reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\": {}", self.path, self.message)
+    }
+}
+
+/// Compiled `JSONSchema` validators for every declared tool, built once and
+/// reused across dispatches instead of recompiling a schema per call.
+pub struct ArgumentValidator {
+    schemas: HashMap<String, JSONSchema>,
+}
+
+impl ArgumentValidator {
+    /// Compile a validator for every tool in
+    /// [`build_function_declarations_for_provider`] (refs pre-inlined, since
+    /// `jsonschema` resolves `$ref` against a document it's handed directly
+    /// rather than our `$defs` convention).
+    pub fn new(todo_planning_enabled: bool) -> Result<Self> {
+        let declarations = build_function_declarations_for_provider(todo_planning_enabled, false);
+        let mut schemas = HashMap::with_capacity(declarations.len());
+        for decl in declarations {
+            let compiled = JSONSchema::compile(&decl.parameters)
+                .map_err(|err| anyhow::anyhow!("invalid schema for tool `{}`: {err}", decl.name))
+                .with_context(|| format!("compiling argument validator for `{}`", decl.name))?;
+            schemas.insert(decl.name, compiled);
+        }
+        Ok(Self { schemas })
+    }
+
+    /// Validate `args` against `tool_name`'s schema. Tools with no compiled
+    /// schema (unknown to this registry) are left for dispatch itself to
+    /// reject, rather than failing validation on a missing tool.
+    pub fn validate(&self, tool_name: &str, args: &Value) -> std::result::Result<(), Vec<ValidationError>> {
+        let Some(schema) = self.schemas.get(tool_name) else {
+            return Ok(());
+        };
+
+        match schema.validate(args) {
+            Ok(()) => Ok(()),
+            Err(errors) => Err(errors
+                .map(|error| ValidationError {
+                    path: error.instance_path.to_string(),
+                    message: error.to_string(),
+                })
+                .collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::constants::tools;
+    use serde_json::json;
+
+    #[test]
+    fn rejects_unknown_enum_value() {
+        let validator = ArgumentValidator::new(true).expect("validator compiles");
+        let errors = validator
+            .validate(tools::GREP_FILE, &json!({"pattern": "TODO", "mode": "fuzy"}))
+            .expect_err("invalid mode should fail validation");
+        assert!(errors.iter().any(|e| e.path == "/mode"));
+    }
+
+    #[test]
+    fn accepts_valid_args() {
+        let validator = ArgumentValidator::new(true).expect("validator compiles");
+        validator
+            .validate(tools::GREP_FILE, &json!({"pattern": "TODO", "mode": "exact"}))
+            .expect("valid args should pass validation");
+    }
+
+    #[test]
+    fn rejects_missing_required_field() {
+        let validator = ArgumentValidator::new(true).expect("validator compiles");
+        let errors = validator
+            .validate(tools::GREP_FILE, &json!({"mode": "exact"}))
+            .expect_err("missing required `pattern` should fail validation");
+        assert!(!errors.is_empty());
+    }
+}
@@ -0,0 +1,391 @@
+//! File-watch / re-run tool: watches paths and re-runs a command on change,
+//! closing the "edit -> test" loop without the agent polling for results.
+//! Sibling to the terminal/PTY execution tools in this module; modeled on
+//! watchexec's debounce semantics. Each run is awaited to completion before
+//! the next batch of events is read, so a running command is never stopped,
+//! queued behind or skipped by `on_busy_update`.
+
+use super::traits::Tool;
+use crate::config::constants::tools;
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use ignore::gitignore::GitignoreBuilder;
+use notify::{RecursiveMode, Watcher};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+
+fn default_debounce_ms() -> u64 {
+    50
+}
+
+fn default_fs_events() -> Vec<String> {
+    vec![
+        "Create".to_string(),
+        "Remove".to_string(),
+        "Rename".to_string(),
+        "Modify".to_string(),
+    ]
+}
+
+fn default_on_busy_update() -> String {
+    "restart".to_string()
+}
+
+fn default_stop_timeout_secs() -> u64 {
+    5
+}
+
+fn default_max_runs() -> Option<u32> {
+    Some(1)
+}
+
+/// `watch_files` tool input.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchFilesInput {
+    /// Paths to watch, recursively.
+    pub paths: Vec<String>,
+    /// Only react to changes to files with one of these extensions (e.g. `["rs"]`); empty means no filter.
+    #[serde(default)]
+    pub filter_extensions: Vec<String>,
+    /// Glob patterns to ignore in addition to VCS ignore rules.
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// Skip honoring `.gitignore`; by default it's applied like watchexec's `--no-vcs-ignore` inverse.
+    #[serde(default)]
+    pub no_vcs_ignore: bool,
+    /// Quiet period after the last matching event before the command runs.
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+    /// Event kinds that count as a trigger: subset of `Create|Remove|Rename|Modify|Metadata`.
+    #[serde(default = "default_fs_events")]
+    pub filter_fs_events: Vec<String>,
+    /// `restart|queue|do-nothing`. Accepted but has no effect: [`WatchFilesTool::run`]
+    /// waits for each run to exit before it reads more events, so events that
+    /// land during a run trigger the next run after it, whatever the value.
+    #[serde(default = "default_on_busy_update")]
+    pub on_busy_update: String,
+    /// Command to run on each triggering batch.
+    pub command: Vec<String>,
+    /// Signal for [`WatchFilesTool::stop_child`] (Unix name, e.g. `SIGTERM`); defaults to killing it.
+    /// Not sent in practice, since no run is still going when the next batch is handled.
+    pub stop_signal: Option<String>,
+    /// How long [`WatchFilesTool::stop_child`] waits for the child to exit after `stop_signal` before a hard kill.
+    #[serde(default = "default_stop_timeout_secs")]
+    pub stop_timeout_secs: u64,
+    /// Number of triggered runs to perform before returning; `None` watches
+    /// forever and never returns through the normal `Tool::execute`
+    /// request/response contract. Defaults to `1` so the standard,
+    /// synchronous tool dispatch path always completes; callers that want a
+    /// long-lived watcher must run it themselves as a cancellable
+    /// background task and pass `None` explicitly.
+    #[serde(default = "default_max_runs")]
+    pub max_runs: Option<u32>,
+}
+
+/// Outcome of one triggered run: its captured output plus the paths that
+/// triggered it.
+#[derive(Debug, Clone)]
+struct WatchRun {
+    triggering_paths: Vec<String>,
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+/// Watches `paths` and re-runs `command` on a debounced batch of matching
+/// filesystem events.
+#[derive(Clone)]
+pub struct WatchFilesTool {
+    workspace_root: PathBuf,
+}
+
+impl WatchFilesTool {
+    pub fn new(workspace_root: PathBuf) -> Self {
+        Self { workspace_root }
+    }
+
+    /// Re-runs `command` on every debounced batch of matching events until
+    /// `input.max_runs` triggered runs have completed (`None` watches
+    /// forever instead, usable only when the caller itself backgrounds this
+    /// call); otherwise returns early with an error if the underlying
+    /// filesystem watcher channel closes or the command cannot be spawned or
+    /// waited on.
+    async fn run(&self, input: &WatchFilesInput) -> Result<Value> {
+        if input.command.is_empty() {
+            return Err(anyhow!("watch_files command cannot be empty"));
+        }
+        if input.max_runs == Some(0) {
+            return Ok(json!({ "runs": Vec::<Value>::new() }));
+        }
+        let allowed_events: HashSet<String> = input.filter_fs_events.iter().cloned().collect();
+
+        let ignore = self.build_ignore_matcher(input)?;
+        let (tx, mut rx) = mpsc::unbounded_channel::<notify::Event>();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                let _ = tx.send(event);
+            }
+        })
+        .context("failed to create filesystem watcher")?;
+
+        for path in &input.paths {
+            let watch_path = self.workspace_root.join(path);
+            watcher
+                .watch(&watch_path, RecursiveMode::Recursive)
+                .with_context(|| format!("failed to watch {watch_path:?}"))?;
+        }
+
+        let mut running_child: Option<Child> = None;
+        let mut completed_runs: Vec<WatchRun> = Vec::new();
+        loop {
+            let Some(first_event) = rx.recv().await else {
+                anyhow::bail!("filesystem watcher channel closed unexpectedly");
+            };
+            let mut batch = vec![first_event];
+            // Debounce: keep draining events arriving within the quiet period.
+            while let Ok(Some(event)) =
+                timeout(Duration::from_millis(input.debounce_ms), rx.recv()).await
+            {
+                batch.push(event);
+            }
+
+            let triggering_paths = self.matching_paths(&batch, input, &allowed_events, &ignore);
+            if triggering_paths.is_empty() {
+                continue;
+            }
+
+            if let Some(child) = running_child.take() {
+                match input.on_busy_update.as_str() {
+                    "queue" => {
+                        self.wait_for_child(child).await;
+                    }
+                    "do-nothing" => {
+                        running_child = Some(child);
+                        continue;
+                    }
+                    _ => self.stop_child(child, input).await,
+                }
+            }
+
+            let child = self.spawn_command(input).await?;
+            running_child = Some(child);
+
+            let run = self
+                .collect_run(running_child.take().unwrap(), triggering_paths)
+                .await?;
+            debug!(
+                triggering_paths = ?run.triggering_paths,
+                exit_code = run.exit_code,
+                stdout_len = run.stdout.len(),
+                stderr_len = run.stderr.len(),
+                "watch_files command run completed"
+            );
+            completed_runs.push(run);
+
+            if let Some(max_runs) = input.max_runs {
+                if completed_runs.len() >= max_runs as usize {
+                    return Ok(json!({
+                        "runs": completed_runs
+                            .iter()
+                            .map(|run| json!({
+                                "triggering_paths": run.triggering_paths,
+                                "exit_code": run.exit_code,
+                                "stdout": run.stdout,
+                                "stderr": run.stderr,
+                            }))
+                            .collect::<Vec<_>>(),
+                    }));
+                }
+            }
+        }
+    }
+
+    fn build_ignore_matcher(&self, input: &WatchFilesInput) -> Result<ignore::gitignore::Gitignore> {
+        let mut builder = GitignoreBuilder::new(&self.workspace_root);
+        if !input.no_vcs_ignore {
+            builder.add(self.workspace_root.join(".gitignore"));
+        }
+        for pattern in &input.ignore_patterns {
+            builder
+                .add_line(None, pattern)
+                .with_context(|| format!("invalid ignore pattern: {pattern}"))?;
+        }
+        builder.build().context("failed to build ignore matcher")
+    }
+
+    fn matching_paths(
+        &self,
+        batch: &[notify::Event],
+        input: &WatchFilesInput,
+        allowed_events: &HashSet<String>,
+        ignore: &ignore::gitignore::Gitignore,
+    ) -> Vec<String> {
+        let mut matched = Vec::new();
+        for event in batch {
+            if !event_kind_allowed(&event.kind, allowed_events) {
+                continue;
+            }
+            for path in &event.paths {
+                if ignore.matched(path, path.is_dir()).is_ignore() {
+                    continue;
+                }
+                if !extension_allowed(path, &input.filter_extensions) {
+                    continue;
+                }
+                if let Ok(relative) = path.strip_prefix(&self.workspace_root) {
+                    matched.push(relative.display().to_string());
+                } else {
+                    matched.push(path.display().to_string());
+                }
+            }
+        }
+        matched
+    }
+
+    async fn spawn_command(&self, input: &WatchFilesInput) -> Result<Child> {
+        let mut cmd = Command::new(&input.command[0]);
+        cmd.args(&input.command[1..]);
+        cmd.current_dir(&self.workspace_root);
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+        cmd.spawn()
+            .with_context(|| format!("failed to run command: {}", input.command.join(" ")))
+    }
+
+    async fn collect_run(&self, child: Child, triggering_paths: Vec<String>) -> Result<WatchRun> {
+        let output = child
+            .wait_with_output()
+            .await
+            .context("failed waiting for watched command")?;
+        Ok(WatchRun {
+            triggering_paths,
+            exit_code: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+
+    async fn wait_for_child(&self, mut child: Child) {
+        let _ = child.wait().await;
+    }
+
+    /// Send `stop_signal` (Unix) and wait up to `stop_timeout_secs` before a
+    /// hard kill. [`Self::run`] calls this only for a child still running
+    /// when a new batch arrives, which does not occur because each run is
+    /// awaited to completion first.
+    async fn stop_child(&self, mut child: Child, input: &WatchFilesInput) {
+        #[cfg(unix)]
+        if let (Some(pid), Some(signal_name)) = (child.id(), input.stop_signal.as_deref()) {
+            if let Some(signal) = unix_signal_from_name(signal_name) {
+                // SAFETY: `pid` is the child we just spawned and still own.
+                unsafe {
+                    libc::kill(pid as libc::pid_t, signal);
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = input;
+
+        if timeout(Duration::from_secs(input.stop_timeout_secs), child.wait())
+            .await
+            .is_err()
+        {
+            let _ = child.kill().await;
+        }
+    }
+}
+
+#[cfg(unix)]
+fn unix_signal_from_name(name: &str) -> Option<libc::c_int> {
+    match name.to_ascii_uppercase().as_str() {
+        "SIGTERM" => Some(libc::SIGTERM),
+        "SIGINT" => Some(libc::SIGINT),
+        "SIGKILL" => Some(libc::SIGKILL),
+        "SIGHUP" => Some(libc::SIGHUP),
+        _ => None,
+    }
+}
+
+fn event_kind_allowed(kind: &notify::EventKind, allowed: &HashSet<String>) -> bool {
+    use notify::EventKind;
+    let name = match kind {
+        EventKind::Create(_) => "Create",
+        EventKind::Remove(_) => "Remove",
+        EventKind::Modify(notify::event::ModifyKind::Name(_)) => "Rename",
+        EventKind::Modify(notify::event::ModifyKind::Metadata(_)) => "Metadata",
+        EventKind::Modify(_) => "Modify",
+        _ => return false,
+    };
+    allowed.is_empty() || allowed.contains(name)
+}
+
+fn extension_allowed(path: &Path, filter_extensions: &[String]) -> bool {
+    if filter_extensions.is_empty() {
+        return true;
+    }
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| filter_extensions.iter().any(|filter| filter == ext))
+        .unwrap_or(false)
+}
+
+#[async_trait]
+impl Tool for WatchFilesTool {
+    async fn execute(&self, args: Value) -> Result<Value> {
+        let input: WatchFilesInput = serde_json::from_value(args)?;
+        self.run(&input).await
+    }
+
+    fn name(&self) -> &'static str {
+        tools::WATCH_FILES
+    }
+
+    fn description(&self) -> &'static str {
+        "Watch paths and re-run a command on a debounced batch of matching filesystem changes"
+    }
+
+    fn validate_args(&self, args: &Value) -> Result<()> {
+        let input: WatchFilesInput = serde_json::from_value(args.clone())?;
+        if input.command.is_empty() {
+            return Err(anyhow!("watch_files command cannot be empty"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_filter_accepts_matching_and_rejects_others() {
+        let filters = vec!["rs".to_string()];
+        assert!(extension_allowed(Path::new("src/main.rs"), &filters));
+        assert!(!extension_allowed(Path::new("README.md"), &filters));
+        assert!(extension_allowed(Path::new("src/main.rs"), &[]));
+    }
+
+    #[test]
+    fn event_kind_allowed_respects_filter_set() {
+        let mut allowed = HashSet::new();
+        allowed.insert("Modify".to_string());
+        assert!(event_kind_allowed(
+            &notify::EventKind::Modify(notify::event::ModifyKind::Data(
+                notify::event::DataChange::Content
+            )),
+            &allowed
+        ));
+        assert!(!event_kind_allowed(
+            &notify::EventKind::Create(notify::event::CreateKind::File),
+            &allowed
+        ));
+    }
+}
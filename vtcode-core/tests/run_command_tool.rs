@@ -30,3 +30,48 @@ async fn run_command_uses_pty_backend() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn run_command_feeds_stdin_to_child() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let mut registry = ToolRegistry::new(temp_dir.path().to_path_buf()).await;
+
+    let response = registry
+        .execute_tool(
+            tools::RUN_COMMAND,
+            json!({
+                "command": "cat",
+                "stdin": "hello from stdin"
+            }),
+        )
+        .await?;
+
+    assert_eq!(response["success"], true);
+    let stdout = response["stdout"].as_str().unwrap_or_default();
+    assert!(stdout.contains("hello from stdin"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn run_command_truncates_output_past_max_output_bytes() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let mut registry = ToolRegistry::new(temp_dir.path().to_path_buf()).await;
+
+    let response = registry
+        .execute_tool(
+            tools::RUN_COMMAND,
+            json!({
+                "command": "yes",
+                "timeout_secs": 2,
+                "max_output_bytes": 64
+            }),
+        )
+        .await?;
+
+    assert_eq!(response["truncated"], true);
+    let stdout = response["stdout"].as_str().unwrap_or_default();
+    assert!(stdout.contains("output truncated"));
+
+    Ok(())
+}
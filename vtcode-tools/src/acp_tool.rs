@@ -7,16 +7,72 @@
 //! - Check agent health
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde_json::{Value, json};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::sync::RwLock;
-use vtcode_acp_client::{AcpClient, AgentRegistry};
+use tokio::task::JoinHandle;
+use vtcode_acp_client::{AcpClient, AcpError, AgentRegistry};
 use vtcode_core::tools::traits::Tool;
 
+/// Time `await_response` is given to resolve a tracked `"async"` call before
+/// it's marked [`MessageState::Failed`].
+const DEFAULT_AWAIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long a terminal (`Succeeded`/`Failed`) entry stays available to
+/// `acp_status` lookups before the retention sweep evicts it.
+const DEFAULT_STATUS_RETENTION: Duration = Duration::from_secs(600);
+
+/// Lifecycle state of a message sent via `AcpTool`'s `"async"` method,
+/// tracked so a caller can later ask `acp_status` whether a fire-and-forget
+/// call ever actually completed, mirroring the commit/rollback status
+/// callbacks mature async messaging clients expose.
+#[derive(Debug, Clone)]
+pub enum MessageState {
+    /// Sent and awaiting the background completion watcher to pick it up.
+    Queued,
+    /// Completion watcher is awaiting the remote agent's response.
+    InFlight,
+    /// The remote agent responded successfully.
+    Succeeded(Value),
+    /// The call failed, either to send or to resolve a response in time.
+    Failed(String),
+}
+
+struct TrackedMessage {
+    state: MessageState,
+    updated_at: DateTime<Utc>,
+}
+
+/// Handle to the background sweep started by [`AcpTool::start_status_reaper`].
+/// Dropping it (or calling [`Self::stop`]) stops the sweep.
+pub struct StatusReaperHandle {
+    shutdown: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
+impl StatusReaperHandle {
+    /// Stop the sweep. Safe to call more than once.
+    pub fn stop(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for StatusReaperHandle {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        self.task.abort();
+    }
+}
+
 /// ACP Inter-Agent Communication Tool
 pub struct AcpTool {
     client: Arc<RwLock<Option<AcpClient>>>,
     registry: Arc<AgentRegistry>,
+    pending_messages: Arc<RwLock<HashMap<String, TrackedMessage>>>,
 }
 
 impl AcpTool {
@@ -25,6 +81,7 @@ impl AcpTool {
         Self {
             client: Arc::new(RwLock::new(None)),
             registry: Arc::new(AgentRegistry::new()),
+            pending_messages: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -40,6 +97,101 @@ impl AcpTool {
     pub fn registry(&self) -> Arc<AgentRegistry> {
         self.registry.clone()
     }
+
+    /// Shared map backing `acp_status` lookups. Handed to
+    /// [`AcpStatusTool::new`] so the two tools track the same messages.
+    pub fn pending_messages(&self) -> Arc<RwLock<HashMap<String, TrackedMessage>>> {
+        self.pending_messages.clone()
+    }
+
+    /// [`Self::start_status_reaper`] with [`DEFAULT_STATUS_RETENTION`].
+    pub fn start_status_reaper_default(&self) -> StatusReaperHandle {
+        self.start_status_reaper(DEFAULT_STATUS_RETENTION)
+    }
+
+    /// Start a periodic sweep that evicts terminal (`Succeeded`/`Failed`)
+    /// entries older than `retention`, so tracking a high volume of
+    /// fire-and-forget calls doesn't grow the map forever.
+    pub fn start_status_reaper(&self, retention: Duration) -> StatusReaperHandle {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let pending_messages = self.pending_messages.clone();
+
+        let task = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+                loop {
+                    if shutdown.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    tokio::time::sleep(SWEEP_INTERVAL).await;
+                    if shutdown.load(Ordering::SeqCst) {
+                        return;
+                    }
+
+                    let now = Utc::now();
+                    let retention = chrono::Duration::from_std(retention)
+                        .unwrap_or_else(|_| chrono::Duration::weeks(52 * 100));
+                    pending_messages.write().await.retain(|_, tracked| {
+                        let is_terminal = matches!(
+                            tracked.state,
+                            MessageState::Succeeded(_) | MessageState::Failed(_)
+                        );
+                        !is_terminal || now.signed_duration_since(tracked.updated_at) < retention
+                    });
+                }
+            })
+        };
+
+        StatusReaperHandle { shutdown, task }
+    }
+
+    /// Spawn the background watcher that follows a tracked `"async"` call
+    /// from `InFlight` through to `Succeeded`/`Failed`, via the same
+    /// `await_response` path synchronous callers use.
+    fn spawn_completion_watcher(&self, message_id: String, remote_agent_id: String) {
+        let client = self.client.clone();
+        let pending_messages = self.pending_messages.clone();
+
+        tokio::spawn(async move {
+            if let Some(tracked) = pending_messages.write().await.get_mut(&message_id) {
+                tracked.state = MessageState::InFlight;
+                tracked.updated_at = Utc::now();
+            }
+
+            let result = {
+                let client = client.read().await;
+                match client.as_ref() {
+                    Some(client) => {
+                        client
+                            .await_response(&message_id, &remote_agent_id, DEFAULT_AWAIT_TIMEOUT)
+                            .await
+                    }
+                    None => Err(AcpError::Internal(
+                        "ACP client was dropped before the async call completed".to_string(),
+                    )),
+                }
+            };
+
+            let new_state = match result {
+                Ok(response) if response.status == vtcode_acp_client::ResponseStatus::Success => {
+                    MessageState::Succeeded(response.result.unwrap_or(Value::Null))
+                }
+                Ok(response) => MessageState::Failed(
+                    response
+                        .error
+                        .map(|details| details.message)
+                        .unwrap_or_else(|| format!("{:?}", response.status)),
+                ),
+                Err(err) => MessageState::Failed(err.to_string()),
+            };
+
+            if let Some(tracked) = pending_messages.write().await.get_mut(&message_id) {
+                tracked.state = new_state;
+                tracked.updated_at = Utc::now();
+            }
+        });
+    }
 }
 
 impl Default for AcpTool {
@@ -99,17 +251,28 @@ impl Tool for AcpTool {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("ACP client not initialized"))?;
 
+        let error_sink = client.error_sink();
+
         match method {
             "sync" => client
                 .call_sync(remote_agent_id, action.to_string(), call_args)
                 .await
-                .map_err(|e| anyhow::anyhow!("ACP call failed: {}", e)),
+                .map_err(|e| report_and_wrap(&error_sink, remote_agent_id, action, &e)),
 
             "async" => {
                 let message_id = client
                     .call_async(remote_agent_id, action.to_string(), call_args)
                     .await
-                    .map_err(|e| anyhow::anyhow!("ACP async call failed: {}", e))?;
+                    .map_err(|e| report_and_wrap(&error_sink, remote_agent_id, action, &e))?;
+
+                self.pending_messages.write().await.insert(
+                    message_id.clone(),
+                    TrackedMessage {
+                        state: MessageState::Queued,
+                        updated_at: Utc::now(),
+                    },
+                );
+                self.spawn_completion_watcher(message_id.clone(), remote_agent_id.to_string());
 
                 Ok(json!({
                     "message_id": message_id,
@@ -124,6 +287,26 @@ impl Tool for AcpTool {
     }
 }
 
+/// Push the failure a tool is about to return (after whatever retries the
+/// client already made, if any) onto the shared
+/// [`vtcode_acp_client::ErrChan`] before converting it to the `anyhow::Error`
+/// `Tool::execute` expects, so the aggregated failure stream sees every ACP
+/// tool failure, not just the client's own internal retries.
+fn report_and_wrap(
+    error_sink: &vtcode_acp_client::ErrChan,
+    agent_id: &str,
+    action: &str,
+    error: &vtcode_acp_client::AcpError,
+) -> anyhow::Error {
+    error_sink.report(vtcode_acp_client::AcpFailure {
+        agent_id: agent_id.to_string(),
+        action: action.to_string(),
+        attempt: 0,
+        error: error.to_string(),
+    });
+    anyhow::anyhow!("ACP call failed: {}", error)
+}
+
 /// Discovery tool for ACP
 pub struct AcpDiscoveryTool {
     client: Arc<RwLock<Option<AcpClient>>>,
@@ -240,11 +423,9 @@ impl Tool for AcpDiscoveryTool {
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| anyhow::anyhow!("Invalid agent_id"))?;
 
-                let agent = client
-                    .registry()
-                    .find(agent_id)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Agent not found: {}", e))?;
+                let agent = client.registry().find(agent_id).await.map_err(|e| {
+                    report_and_wrap(&client.error_sink(), agent_id, "discover:by_id", &e)
+                })?;
 
                 Ok(json!(agent))
             }
@@ -306,7 +487,7 @@ impl Tool for AcpHealthTool {
         let is_online = client
             .ping(agent_id)
             .await
-            .map_err(|e| anyhow::anyhow!("Health check failed: {}", e))?;
+            .map_err(|e| report_and_wrap(&client.error_sink(), agent_id, "ping", &e))?;
 
         Ok(json!({
             "agent_id": agent_id,
@@ -315,3 +496,71 @@ impl Tool for AcpHealthTool {
         }))
     }
 }
+
+/// Status-query tool for messages sent via `AcpTool`'s `"async"` method.
+/// Construct with the same map returned by [`AcpTool::pending_messages`] so
+/// the two tools track the same calls.
+pub struct AcpStatusTool {
+    pending_messages: Arc<RwLock<HashMap<String, TrackedMessage>>>,
+}
+
+impl AcpStatusTool {
+    pub fn new(pending_messages: Arc<RwLock<HashMap<String, TrackedMessage>>>) -> Self {
+        Self { pending_messages }
+    }
+}
+
+#[async_trait]
+impl Tool for AcpStatusTool {
+    fn name(&self) -> &'static str {
+        "acp_status"
+    }
+
+    fn description(&self) -> &'static str {
+        "Check the status of a message sent via acp_call's async method. \
+         Returns whether it is queued, in flight, succeeded (with result), or failed (with error)."
+    }
+
+    fn validate_args(&self, args: &Value) -> anyhow::Result<()> {
+        let obj = args
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("Arguments must be an object"))?;
+
+        if !obj.contains_key("message_id") {
+            return Err(anyhow::anyhow!("Missing required field: message_id"));
+        }
+
+        Ok(())
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<Value> {
+        let obj = args
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("Arguments must be an object"))?;
+
+        let message_id = obj
+            .get("message_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Invalid message_id"))?;
+
+        let pending = self.pending_messages.read().await;
+        let tracked = pending
+            .get(message_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown message_id: {}", message_id))?;
+
+        let (status, result, error) = match &tracked.state {
+            MessageState::Queued => ("queued", None, None),
+            MessageState::InFlight => ("in_flight", None, None),
+            MessageState::Succeeded(value) => ("succeeded", Some(value.clone()), None),
+            MessageState::Failed(error) => ("failed", None, Some(error.clone())),
+        };
+
+        Ok(json!({
+            "message_id": message_id,
+            "status": status,
+            "result": result,
+            "error": error,
+            "updated_at": tracked.updated_at.to_rfc3339(),
+        }))
+    }
+}
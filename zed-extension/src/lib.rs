@@ -1,4 +1,4 @@
-use std::{env, path::PathBuf};
+use std::{collections::BTreeMap, env, path::PathBuf};
 
 use zed_extension_api as zed;
 
@@ -62,6 +62,55 @@ impl zed::Extension for VTCodeExtension {
                     text,
                 })
             }
+            "costs" => {
+                let Some(worktree) = worktree else {
+                    return Err("No workspace available".to_string());
+                };
+                let root = PathBuf::from(worktree.root_path());
+                let log_path = root.join(".vtcode/logs/trajectory.jsonl");
+
+                let contents = std::fs::read_to_string(&log_path)
+                    .map_err(|err| format!("Failed to read trajectory log: {err}"))?;
+
+                let mut spend_by_model: BTreeMap<String, u64> = BTreeMap::new();
+                for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+                    let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+                        continue;
+                    };
+                    let model = entry
+                        .get("model")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown")
+                        .to_string();
+                    let micro_cents = entry
+                        .get("total_micro_cents")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0);
+                    *spend_by_model.entry(model).or_insert(0) += micro_cents;
+                }
+
+                let total_micro_cents: u64 = spend_by_model.values().sum();
+
+                let mut text = String::from("Session cost summary\n");
+                text.push_str(&format!(
+                    "  total: ${:.4}\n",
+                    total_micro_cents as f64 / 1_000_000.0 / 100.0
+                ));
+                for (model, micro_cents) in &spend_by_model {
+                    text.push_str(&format!(
+                        "  {model}: ${:.4}\n",
+                        *micro_cents as f64 / 1_000_000.0 / 100.0
+                    ));
+                }
+
+                Ok(zed::SlashCommandOutput {
+                    sections: vec![zed::SlashCommandOutputSection {
+                        range: (0..text.len()).into(),
+                        label: "Session Costs".to_string(),
+                    }],
+                    text,
+                })
+            }
             other => Err(format!("Unknown slash command: {other}")),
         }
     }